@@ -0,0 +1,733 @@
+use audiopus::coder::Encoder as OpusEncoder;
+use audiopus::{Application, Bitrate, Channels, SampleRate};
+use flacenc::component::BitRepr;
+use flacenc::error::Verify;
+use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+use once_cell::sync::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use crate::audio_conditioning::WHISPER_SAMPLE_RATE;
+use crate::audio_decode::load_audio_for_whisper;
+use crate::file_utils::recordings_directory;
+use crate::history::with_database;
+use crate::settings_store::{load_settings, save_settings};
+
+// 20 ms frames at 16 kHz; 24 kbit/s is transparent for 16 kHz speech
+const OPUS_FRAME_SAMPLES: usize = 320;
+const OPUS_BITRATE: i32 = 24_000;
+const OPUS_MAX_PACKET: usize = 4_000;
+// Ogg Opus granule positions and pre-skip always count 48 kHz samples
+const OPUS_GRANULE_RATE: u64 = 48_000;
+const OGG_SERIAL: u32 = 0x566f_4963;
+
+const SETTINGS_FILE: &str = "archive.json";
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// Files this young may still be waiting for their history row to be updated
+const ORPHAN_GRACE: Duration = Duration::from_secs(10 * 60);
+
+/// Compression used for archived recordings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveFormat {
+    #[default]
+    Opus, // Lossy, roughly 3 KB per second
+    Flac, // Lossless 16-bit, roughly 20 KB per second
+}
+
+impl ArchiveFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Opus => "opus",
+            ArchiveFormat::Flac => "flac",
+        }
+    }
+
+    pub fn from_file_name(name: &str) -> Option<ArchiveFormat> {
+        match Path::new(name).extension()?.to_str()? {
+            "opus" => Some(ArchiveFormat::Opus),
+            "flac" => Some(ArchiveFormat::Flac),
+            _ => None,
+        }
+    }
+}
+
+/// Which archived audio the cleanup task removes. Transcripts are kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    pub keep_days: Option<u32>,    // Remove audio older than this; None keeps it forever
+    pub max_total_mb: Option<u64>, // Remove the oldest audio beyond this total size
+    pub keep_starred: bool,        // Never remove audio of starred recordings
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        RetentionPolicy {
+            keep_days: Some(30),
+            max_total_mb: Some(1024),
+            keep_starred: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveSettings {
+    pub archive_dictations: bool, // Store the audio of hotkey recordings with their history entry
+    pub format: ArchiveFormat,
+    pub retention: RetentionPolicy,
+}
+
+impl Default for ArchiveSettings {
+    fn default() -> Self {
+        ArchiveSettings {
+            archive_dictations: true,
+            format: ArchiveFormat::default(),
+            retention: RetentionPolicy::default(),
+        }
+    }
+}
+
+impl ArchiveSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.retention.keep_days == Some(0) {
+            return Err("Retention must keep audio for at least one day".to_string());
+        }
+        if self.retention.max_total_mb == Some(0) {
+            return Err("Archive size limit must be at least 1 MB".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Audio stored for a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedAudio {
+    pub recording_id: String,
+    pub format: ArchiveFormat,
+    pub size: u64, // Bytes on disk
+    pub duration_ms: u64,
+}
+
+/// Result of a retention run, also emitted as `archive-cleaned` when anything was removed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CleanupReport {
+    pub removed_files: usize,
+    pub freed_bytes: u64,
+    pub remaining_bytes: u64, // Total size of the archive after cleanup
+}
+
+lazy_static::lazy_static! {
+    static ref ARCHIVE_SETTINGS: Mutex<ArchiveSettings> = Mutex::new(ArchiveSettings::default());
+}
+
+pub(crate) fn archive_settings() -> ArchiveSettings {
+    ARCHIVE_SETTINGS.lock().unwrap().clone()
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn opus_head(pre_skip: u16) -> Vec<u8> {
+    let mut head = b"OpusHead".to_vec();
+    head.push(1); // Version
+    head.push(1); // Channel count
+    head.extend_from_slice(&pre_skip.to_le_bytes());
+    head.extend_from_slice(&WHISPER_SAMPLE_RATE.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // Output gain
+    head.push(0); // Channel mapping family: mono/stereo
+    head
+}
+
+fn opus_tags() -> Vec<u8> {
+    let vendor = concat!("voice-intelligence ", env!("CARGO_PKG_VERSION"));
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor.as_bytes());
+    tags.extend_from_slice(&0u32.to_le_bytes()); // No user comments
+    tags
+}
+
+/// Encode 16 kHz mono audio as Ogg Opus
+fn encode_opus(samples: &[f32]) -> Result<Vec<u8>, String> {
+    let mut encoder = OpusEncoder::new(SampleRate::Hz16000, Channels::Mono, Application::Voip)
+        .map_err(|e| format!("Failed to create Opus encoder: {}", e))?;
+    encoder
+        .set_bitrate(Bitrate::BitsPerSecond(OPUS_BITRATE))
+        .map_err(|e| format!("Failed to set Opus bitrate: {}", e))?;
+    let lookahead = encoder
+        .lookahead()
+        .map_err(|e| format!("Failed to query Opus encoder delay: {}", e))? as usize;
+
+    let granule_scale = OPUS_GRANULE_RATE / WHISPER_SAMPLE_RATE as u64;
+    let pre_skip = lookahead as u64 * granule_scale;
+
+    let mut writer = PacketWriter::new(Vec::new());
+    let write_error = |e: std::io::Error| format!("Failed to write Ogg stream: {}", e);
+    writer
+        .write_packet(opus_head(pre_skip as u16).into_boxed_slice(), OGG_SERIAL, PacketWriteEndInfo::EndPage, 0)
+        .map_err(write_error)?;
+    writer
+        .write_packet(opus_tags().into_boxed_slice(), OGG_SERIAL, PacketWriteEndInfo::EndPage, 0)
+        .map_err(write_error)?;
+
+    // Keep encoding silence until the encoder delay has been flushed out
+    let frame_count = (samples.len() + lookahead).div_ceil(OPUS_FRAME_SAMPLES).max(1);
+    let mut frame = [0f32; OPUS_FRAME_SAMPLES];
+    let mut packet = vec![0u8; OPUS_MAX_PACKET];
+
+    for index in 0..frame_count {
+        let start = (index * OPUS_FRAME_SAMPLES).min(samples.len());
+        let end = ((index + 1) * OPUS_FRAME_SAMPLES).min(samples.len());
+        frame.fill(0.0);
+        frame[..end - start].copy_from_slice(&samples[start..end]);
+
+        let size = encoder
+            .encode_float(&frame, &mut packet)
+            .map_err(|e| format!("Opus encoding failed: {}", e))?;
+
+        let last = index + 1 == frame_count;
+        // The final granule position trims the padding off the end
+        let (info, granule) = if last {
+            (PacketWriteEndInfo::EndStream, pre_skip + samples.len() as u64 * granule_scale)
+        } else {
+            (PacketWriteEndInfo::NormalPacket, ((index + 1) * OPUS_FRAME_SAMPLES) as u64 * granule_scale)
+        };
+        writer
+            .write_packet(packet[..size].to_vec().into_boxed_slice(), OGG_SERIAL, info, granule)
+            .map_err(write_error)?;
+    }
+
+    Ok(writer.into_inner())
+}
+
+/// Encode 16 kHz mono audio as 16-bit FLAC
+fn encode_flac(samples: &[f32]) -> Result<Vec<u8>, String> {
+    let pcm: Vec<i32> = samples
+        .iter()
+        .map(|&sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i32)
+        .collect();
+
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|(_, e)| format!("Invalid FLAC encoder configuration: {:?}", e))?;
+    let source = flacenc::source::MemSource::from_samples(&pcm, 1, 16, WHISPER_SAMPLE_RATE as usize);
+    let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| format!("FLAC encoding failed: {:?}", e))?;
+
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| format!("Failed to write FLAC stream: {:?}", e))?;
+    Ok(sink.as_slice().to_vec())
+}
+
+/// Path of an archived file, refusing names that would leave the recordings directory
+fn archive_path(app_handle: &AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    if Path::new(file_name).file_name().and_then(|name| name.to_str()) != Some(file_name) {
+        return Err(format!("Invalid archive file name: {}", file_name));
+    }
+    Ok(recordings_directory(app_handle)?.join(file_name))
+}
+
+/// Name of the file archived for a recording, if any
+fn audio_file_of(connection: &Connection, recording_id: &str) -> Result<Option<String>, String> {
+    connection
+        .query_row("SELECT audio_file FROM recordings WHERE id = ?1", [recording_id], |row| row.get(0))
+        .optional()
+        .map_err(|e| format!("History database error: {}", e))?
+        .ok_or_else(|| format!("Recording not found: {}", recording_id))
+}
+
+pub(crate) fn remove_archived_audio(app_handle: &AppHandle, file_name: &str) -> Result<(), String> {
+    match fs::remove_file(archive_path(app_handle, file_name)?) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to delete archived audio: {}", e)),
+    }
+}
+
+/// Compressed audio written under a temporary name, waiting for the recording
+/// it belongs to. The file is deleted if this is dropped before `store`.
+pub(crate) struct StagedAudio {
+    temp_path: PathBuf,
+    directory: PathBuf,
+    format: ArchiveFormat,
+    size: u64,
+}
+
+impl StagedAudio {
+    /// Name the file gets once stored for `recording_id`
+    pub(crate) fn file_name(&self, recording_id: &str) -> String {
+        format!("{}.{}", recording_id, self.format.extension())
+    }
+
+    pub(crate) fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Move the file to its final name and return that path
+    pub(crate) fn store(self, recording_id: &str) -> Result<PathBuf, String> {
+        let path = self.directory.join(self.file_name(recording_id));
+        fs::rename(&self.temp_path, &path).map_err(|e| format!("Failed to store archived audio: {}", e))?;
+        Ok(path)
+    }
+}
+
+impl Drop for StagedAudio {
+    fn drop(&mut self) {
+        // Already gone after a successful `store`
+        let _ = fs::remove_file(&self.temp_path);
+    }
+}
+
+/// Compress 16 kHz mono audio in the configured format and write it to the
+/// recordings directory under a temporary name, so a crash never leaves a
+/// truncated file behind
+pub(crate) fn stage_audio(app_handle: &AppHandle, samples: &[f32]) -> Result<StagedAudio, String> {
+    let format = archive_settings().format;
+    let encoded = match format {
+        ArchiveFormat::Opus => encode_opus(samples)?,
+        ArchiveFormat::Flac => encode_flac(samples)?,
+    };
+
+    let directory = recordings_directory(app_handle)?;
+    let staged = StagedAudio {
+        temp_path: directory.join(format!("{}.tmp", uuid::Uuid::new_v4())),
+        directory,
+        format,
+        size: encoded.len() as u64,
+    };
+    fs::write(&staged.temp_path, &encoded).map_err(|e| format!("Failed to write archived audio: {}", e))?;
+
+    Ok(staged)
+}
+
+/// Compress 16 kHz mono audio and store it with a history recording,
+/// replacing any audio archived for it before
+pub(crate) fn archive_recording_audio(
+    app_handle: &AppHandle,
+    recording_id: &str,
+    samples: &[f32],
+) -> Result<ArchivedAudio, String> {
+    let previous = with_database(app_handle, |connection| audio_file_of(connection, recording_id))?;
+
+    let staged = stage_audio(app_handle, samples)?;
+    let file_name = staged.file_name(recording_id);
+    let format = staged.format;
+    let size = staged.size();
+    staged.store(recording_id)?;
+
+    with_database(app_handle, |connection| {
+        connection
+            .execute(
+                "UPDATE recordings SET audio_file = ?1, audio_size = ?2 WHERE id = ?3",
+                params![file_name, size as i64, recording_id],
+            )
+            .map_err(|e| format!("History database error: {}", e))
+    })?;
+
+    if let Some(previous) = previous.filter(|previous| *previous != file_name) {
+        remove_archived_audio(app_handle, &previous)?;
+    }
+
+    Ok(ArchivedAudio {
+        recording_id: recording_id.to_string(),
+        format,
+        size,
+        duration_ms: samples.len() as u64 * 1000 / WHISPER_SAMPLE_RATE as u64,
+    })
+}
+
+struct ArchivedFile {
+    recording_id: String,
+    created_at: i64,
+    starred: bool,
+    file_name: String,
+    size: u64,
+}
+
+/// Apply the retention policy: drop expired audio oldest first, then any
+/// files in the recordings directory that no recording refers to
+pub fn clean_up_archive(app_handle: &AppHandle) -> Result<CleanupReport, String> {
+    let policy = archive_settings().retention;
+    let directory = recordings_directory(app_handle)?;
+
+    let archived = with_database(app_handle, |connection| {
+        let mut statement = connection
+            .prepare(
+                "SELECT id, created_at, starred, audio_file, audio_size FROM recordings
+                 WHERE audio_file IS NOT NULL ORDER BY created_at",
+            )
+            .map_err(|e| format!("History database error: {}", e))?;
+        let files = statement
+            .query_map([], |row| {
+                Ok(ArchivedFile {
+                    recording_id: row.get(0)?,
+                    created_at: row.get(1)?,
+                    starred: row.get(2)?,
+                    file_name: row.get(3)?,
+                    size: row.get::<_, Option<i64>>(4)?.unwrap_or(0) as u64,
+                })
+            })
+            .map_err(|e| format!("History database error: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("History database error: {}", e))?;
+        Ok(files)
+    })?;
+
+    let mut report = CleanupReport::default();
+    let referenced = apply_retention(archived, &policy, now_ms(), &mut report, |file| {
+        remove_archived_audio(app_handle, &file.file_name)?;
+        with_database(app_handle, |connection| {
+            connection
+                .execute(
+                    "UPDATE recordings SET audio_file = NULL, audio_size = NULL WHERE id = ?1",
+                    [&file.recording_id],
+                )
+                .map_err(|e| format!("History database error: {}", e))
+        })
+        .map(|_| ())
+    });
+
+    remove_orphans(&directory, &referenced, ORPHAN_GRACE, &mut report)?;
+    Ok(report)
+}
+
+/// Remove the files `policy` expires, oldest first, using `remove`. Files
+/// that cannot be removed are kept. Returns the names of the kept files.
+fn apply_retention(
+    archived: Vec<ArchivedFile>,
+    policy: &RetentionPolicy,
+    now: i64,
+    report: &mut CleanupReport,
+    mut remove: impl FnMut(&ArchivedFile) -> Result<(), String>,
+) -> HashSet<String> {
+    let cutoff = policy.keep_days.map(|days| now - days as i64 * DAY_MS);
+    let max_bytes = policy.max_total_mb.map(|mb| mb * 1024 * 1024);
+    let mut total: u64 = archived.iter().map(|file| file.size).sum();
+    let mut referenced = HashSet::new();
+
+    for file in archived {
+        let expired = cutoff.is_some_and(|cutoff| file.created_at < cutoff);
+        let over_budget = max_bytes.is_some_and(|max_bytes| total > max_bytes);
+        if (policy.keep_starred && file.starred) || !(expired || over_budget) {
+            referenced.insert(file.file_name);
+            continue;
+        }
+
+        if let Err(e) = remove(&file) {
+            eprintln!("Archive cleanup: {}", e);
+            referenced.insert(file.file_name);
+            continue;
+        }
+
+        total -= file.size;
+        report.removed_files += 1;
+        report.freed_bytes += file.size;
+    }
+
+    report.remaining_bytes = total;
+    referenced
+}
+
+/// Delete leftovers of deleted recordings and interrupted writes: files in
+/// `directory` that are not `referenced` and older than `grace`
+fn remove_orphans(
+    directory: &Path,
+    referenced: &HashSet<String>,
+    grace: Duration,
+    report: &mut CleanupReport,
+) -> Result<(), String> {
+    let entries = fs::read_dir(directory).map_err(|e| format!("Failed to read recordings directory: {}", e))?;
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else { continue };
+        let file_name = entry.file_name().to_string_lossy().into_owned();
+        let recent = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .map_or(true, |age| age < grace);
+        if !metadata.is_file() || recent || referenced.contains(&file_name) {
+            continue;
+        }
+        if fs::remove_file(entry.path()).is_ok() {
+            report.removed_files += 1;
+            report.freed_bytes += metadata.len();
+        }
+    }
+    Ok(())
+}
+
+/// Load the stored archive settings, then run the retention policy now and
+/// every hour for the rest of the session
+pub fn start_archive_cleanup(app_handle: &AppHandle) {
+    match load_settings(app_handle, SETTINGS_FILE, ArchiveSettings::validate) {
+        Ok(Some(settings)) => *ARCHIVE_SETTINGS.lock().unwrap() = settings,
+        Ok(None) => {}
+        Err(e) => {
+            // Cleaning up with the default policy could delete audio the user chose to keep
+            eprintln!("Archive cleanup disabled, stored archive settings are unusable: {}", e);
+            return;
+        }
+    }
+
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let cleanup_app = app.clone();
+            match tauri::async_runtime::spawn_blocking(move || clean_up_archive(&cleanup_app)).await {
+                Ok(Ok(report)) if report.removed_files > 0 => {
+                    let _ = app.emit("archive-cleaned", report);
+                }
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => eprintln!("Archive cleanup failed: {}", e),
+                Err(e) => eprintln!("Archive cleanup task failed: {}", e),
+            }
+        }
+    });
+}
+
+fn archived_file(app_handle: &AppHandle, recording_id: &str) -> Result<PathBuf, String> {
+    let file_name = with_database(app_handle, |connection| audio_file_of(connection, recording_id))?;
+    let file_name = file_name.ok_or_else(|| format!("No audio archived for recording {}", recording_id))?;
+    archive_path(app_handle, &file_name)
+}
+
+#[tauri::command]
+pub async fn get_archive_settings() -> Result<ArchiveSettings, String> {
+    Ok(archive_settings())
+}
+
+#[tauri::command]
+pub async fn set_archive_settings(settings: ArchiveSettings, app_handle: AppHandle) -> Result<(), String> {
+    settings.validate()?;
+    save_settings(&app_handle, SETTINGS_FILE, &settings)?;
+    *ARCHIVE_SETTINGS.lock().unwrap() = settings;
+    Ok(())
+}
+
+/// Decode an audio file and archive it as the audio of an existing recording
+#[tauri::command]
+pub async fn archive_audio_file(
+    recording_id: String,
+    path: String,
+    app_handle: AppHandle,
+) -> Result<ArchivedAudio, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let samples = load_audio_for_whisper(Path::new(&path))?;
+        archive_recording_audio(&app_handle, &recording_id, &samples)
+    })
+    .await
+    .map_err(|e| format!("Archive task failed: {}", e))?
+}
+
+/// Path of a recording's archived audio file
+#[tauri::command]
+pub async fn get_recording_audio_path(recording_id: String, app_handle: AppHandle) -> Result<String, String> {
+    archived_file(&app_handle, &recording_id)?
+        .to_str()
+        .ok_or("Invalid path".to_string())
+        .map(|s| s.to_string())
+}
+
+/// Raw bytes of a recording's archived audio (Ogg Opus or FLAC) for playback
+#[tauri::command]
+pub async fn get_recording_audio(recording_id: String, app_handle: AppHandle) -> Result<tauri::ipc::Response, String> {
+    let bytes = tauri::async_runtime::spawn_blocking(move || {
+        let path = archived_file(&app_handle, &recording_id)?;
+        fs::read(&path).map_err(|e| format!("Failed to read archived audio: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Archive read task failed: {}", e))??;
+    Ok(tauri::ipc::Response::new(bytes))
+}
+
+#[tauri::command]
+pub async fn run_archive_cleanup(app_handle: AppHandle) -> Result<CleanupReport, String> {
+    tauri::async_runtime::spawn_blocking(move || clean_up_archive(&app_handle))
+        .await
+        .map_err(|e| format!("Archive cleanup task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+    const NOW: i64 = 100 * DAY_MS;
+
+    fn temp_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("voice-intelligence-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    /// An archived file of `size_mb` written `age_days` before `NOW`
+    fn archived(directory: &Path, name: &str, age_days: i64, size_mb: u64, starred: bool) -> ArchivedFile {
+        let file_name = format!("{}.opus", name);
+        fs::write(directory.join(&file_name), b"audio").unwrap();
+        ArchivedFile {
+            recording_id: name.to_string(),
+            created_at: NOW - age_days * DAY_MS,
+            starred,
+            file_name,
+            size: size_mb * MB,
+        }
+    }
+
+    /// Run the policy against files in `directory`; returns what is left on disk
+    fn retain(directory: &Path, files: Vec<ArchivedFile>, policy: RetentionPolicy) -> (CleanupReport, Vec<String>) {
+        let mut report = CleanupReport::default();
+        let referenced = apply_retention(files, &policy, NOW, &mut report, |file| {
+            fs::remove_file(directory.join(&file.file_name)).map_err(|e| e.to_string())
+        });
+
+        let mut left: Vec<String> = fs::read_dir(directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        let mut kept: Vec<String> = referenced.into_iter().collect();
+        kept.sort();
+        assert_eq!(left, kept);
+        (report, left)
+    }
+
+    #[test]
+    fn expired_audio_is_removed() {
+        let directory = temp_directory("retention-days");
+        let files = vec![
+            archived(&directory, "old", 40, 1, false),
+            archived(&directory, "old-starred", 40, 1, true),
+            archived(&directory, "new", 5, 1, false),
+        ];
+        let policy = RetentionPolicy {
+            keep_days: Some(30),
+            max_total_mb: None,
+            keep_starred: true,
+        };
+
+        let (report, left) = retain(&directory, files, policy);
+
+        assert_eq!(left, vec!["new.opus", "old-starred.opus"]);
+        assert_eq!(report.removed_files, 1);
+        assert_eq!(report.freed_bytes, MB);
+        assert_eq!(report.remaining_bytes, 2 * MB);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn oldest_audio_goes_first_when_over_budget() {
+        let directory = temp_directory("retention-size");
+        // Ordered oldest first, as the database query returns them
+        let files = vec![
+            archived(&directory, "a", 3, 2, false),
+            archived(&directory, "b", 2, 2, false),
+            archived(&directory, "c", 1, 2, false),
+        ];
+        let policy = RetentionPolicy {
+            keep_days: None,
+            max_total_mb: Some(3),
+            keep_starred: true,
+        };
+
+        let (report, left) = retain(&directory, files, policy);
+
+        assert_eq!(left, vec!["c.opus"]);
+        assert_eq!(report.removed_files, 2);
+        assert_eq!(report.remaining_bytes, 2 * MB);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn starred_audio_is_only_kept_when_asked() {
+        let directory = temp_directory("retention-starred");
+        let policy = RetentionPolicy {
+            keep_days: Some(30),
+            max_total_mb: Some(1),
+            keep_starred: true,
+        };
+        let files = vec![
+            archived(&directory, "starred", 40, 2, true),
+            archived(&directory, "plain", 1, 1, false),
+        ];
+
+        // The starred file alone exceeds the budget, so the other one goes
+        let (_, left) = retain(&directory, files, policy.clone());
+        assert_eq!(left, vec!["starred.opus"]);
+
+        let files = vec![archived(&directory, "starred", 40, 2, true)];
+        let (report, left) = retain(&directory, files, RetentionPolicy {
+            keep_starred: false,
+            ..policy
+        });
+        assert!(left.is_empty());
+        assert_eq!(report.remaining_bytes, 0);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn files_that_cannot_be_removed_are_kept() {
+        let mut report = CleanupReport::default();
+        let files = vec![ArchivedFile {
+            recording_id: "locked".to_string(),
+            created_at: 0,
+            starred: false,
+            file_name: "locked.opus".to_string(),
+            size: MB,
+        }];
+
+        let referenced = apply_retention(files, &RetentionPolicy::default(), NOW, &mut report, |_| {
+            Err("permission denied".to_string())
+        });
+
+        assert!(referenced.contains("locked.opus"));
+        assert_eq!(report.removed_files, 0);
+        assert_eq!(report.remaining_bytes, MB);
+    }
+
+    #[test]
+    fn orphans_are_removed_after_the_grace_period() {
+        let directory = temp_directory("retention-orphans");
+        let old = SystemTime::now() - ORPHAN_GRACE - Duration::from_secs(60);
+        for name in ["kept.opus", "orphan.opus", "staging.tmp"] {
+            fs::write(directory.join(name), b"audio").unwrap();
+        }
+        for name in ["kept.opus", "orphan.opus"] {
+            fs::File::options()
+                .write(true)
+                .open(directory.join(name))
+                .unwrap()
+                .set_modified(old)
+                .unwrap();
+        }
+        let referenced = HashSet::from(["kept.opus".to_string()]);
+        let mut report = CleanupReport::default();
+
+        remove_orphans(&directory, &referenced, ORPHAN_GRACE, &mut report).unwrap();
+
+        // The young staging file may still be waiting for its recording row
+        assert!(directory.join("kept.opus").exists());
+        assert!(!directory.join("orphan.opus").exists());
+        assert!(directory.join("staging.tmp").exists());
+        assert_eq!(report.removed_files, 1);
+        assert_eq!(report.freed_bytes, 5);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,652 @@
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SampleFormat, SizedSample};
+use once_cell::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::audio_conditioning::{condition_audio, AudioFormat, WHISPER_SAMPLE_RATE};
+
+// Environment variable listing WAV files to expose as fake input devices,
+// separated like PATH. Lets capture run on machines without a microphone.
+const FAKE_INPUT_ENV: &str = "VOICE_INTELLIGENCE_FAKE_INPUT";
+
+const SYSTEM_DEVICE_PREFIX: &str = "system:";
+const FILE_DEVICE_PREFIX: &str = "file:";
+
+// Minimum interval between `audio-level` events
+const LEVEL_EVENT_INTERVAL: Duration = Duration::from_millis(50);
+
+// Block size the fake device delivers at a time, matching a typical driver buffer
+const FAKE_BLOCK_MS: u64 = 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputDeviceKind {
+    System, // Microphone provided by the OS audio host
+    File,   // WAV file played back in real time
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputDeviceInfo {
+    pub id: String,
+    pub name: String,
+    pub kind: InputDeviceKind,
+    pub is_default: bool,
+}
+
+/// Payload of the `audio-level` event emitted while capturing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioLevel {
+    pub rms: f32,        // 0.0 to 1.0
+    pub peak: f32,       // 0.0 to 1.0
+    pub elapsed_ms: u64, // Time since capture started
+}
+
+/// Reported by `get_capture_status` and the `capture-state` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureStatus {
+    pub capturing: bool,
+    pub device_id: Option<String>, // Selected device, None for the system default
+    pub elapsed_ms: Option<u64>,   // Set while capturing
+}
+
+/// Summary of a finished capture; the audio itself stays in the backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapturedAudio {
+    pub device_name: String,
+    pub duration_ms: u64,
+}
+
+/// Receives samples from an input device on its capture thread. Clones
+/// share the same buffer, so a device callback can own one.
+#[derive(Clone)]
+pub struct CaptureSink {
+    buffer: Arc<Mutex<Vec<f32>>>,
+    meter: Arc<Mutex<LevelMeter>>,
+    exhausted: Arc<AtomicBool>, // The device has nothing more to deliver
+}
+
+struct LevelMeter {
+    app_handle: Option<AppHandle>,
+    started: Instant,
+    last_emit: Option<Instant>,
+    channels: usize,
+    sum_squares: f64,
+    count: usize,
+    peak: f32,
+}
+
+impl CaptureSink {
+    fn new(app_handle: Option<AppHandle>) -> Self {
+        CaptureSink {
+            buffer: Arc::new(Mutex::new(Vec::new())),
+            meter: Arc::new(Mutex::new(LevelMeter {
+                app_handle,
+                started: Instant::now(),
+                last_emit: None,
+                channels: 1,
+                sum_squares: 0.0,
+                count: 0,
+                peak: 0.0,
+            })),
+            exhausted: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn set_channels(&self, channels: usize) {
+        self.meter.lock().unwrap().channels = channels.max(1);
+    }
+
+    /// Append interleaved samples and update the level meter
+    pub fn push(&self, samples: &[f32]) {
+        self.buffer.lock().unwrap().extend_from_slice(samples);
+        self.meter.lock().unwrap().measure(samples);
+    }
+
+    /// Mark the end of the input, e.g. a fake device reaching the end of its file
+    pub fn end_of_input(&self) {
+        self.exhausted.store(true, Ordering::SeqCst);
+    }
+
+    #[cfg(test)]
+    fn is_exhausted(&self) -> bool {
+        self.exhausted.load(Ordering::SeqCst)
+    }
+
+    fn take(&self) -> Vec<f32> {
+        std::mem::take(&mut *self.buffer.lock().unwrap())
+    }
+}
+
+impl LevelMeter {
+    fn measure(&mut self, samples: &[f32]) {
+        for sample in samples {
+            self.sum_squares += (*sample as f64) * (*sample as f64);
+            self.peak = self.peak.max(sample.abs());
+        }
+        self.count += samples.len();
+
+        let due = self
+            .last_emit
+            .map(|last| last.elapsed() >= LEVEL_EVENT_INTERVAL)
+            .unwrap_or(true);
+        if !due || self.count < self.channels {
+            return;
+        }
+
+        let level = AudioLevel {
+            rms: ((self.sum_squares / self.count as f64).sqrt() as f32).min(1.0),
+            peak: self.peak.min(1.0),
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+        };
+        if let Some(app) = &self.app_handle {
+            let _ = app.emit("audio-level", level);
+        }
+
+        self.last_emit = Some(Instant::now());
+        self.sum_squares = 0.0;
+        self.count = 0;
+        self.peak = 0.0;
+    }
+}
+
+/// A source of audio for the capture subsystem. Devices are opened on the
+/// capture thread, which matters for hosts whose streams are not `Send`.
+pub trait InputDevice {
+    fn info(&self) -> InputDeviceInfo;
+
+    /// Sample rate and channel count of the samples pushed into the sink
+    fn format(&self) -> Result<AudioFormat, String>;
+
+    /// Push samples into `sink` until a message arrives on `stop` or it disconnects.
+    /// `started` is called once samples are flowing.
+    fn capture(&mut self, sink: &CaptureSink, stop: &Receiver<()>, started: &dyn Fn()) -> Result<(), String>;
+}
+
+/// Microphone provided by the OS audio host (ALSA/PulseAudio, CoreAudio, WASAPI)
+pub struct SystemInputDevice {
+    device: cpal::Device,
+    info: InputDeviceInfo,
+}
+
+impl SystemInputDevice {
+    fn config(&self) -> Result<cpal::SupportedStreamConfig, String> {
+        self.device
+            .default_input_config()
+            .map_err(|e| format!("Failed to query input device: {}", e))
+    }
+
+    fn build_stream<T>(&self, config: &cpal::StreamConfig, sink: &CaptureSink) -> Result<cpal::Stream, String>
+    where
+        T: SizedSample,
+        f32: FromSample<T>,
+    {
+        let sink = sink.clone();
+        self.device
+            .build_input_stream(
+                config,
+                move |data: &[T], _: &cpal::InputCallbackInfo| {
+                    let samples: Vec<f32> = data.iter().map(|sample| f32::from_sample(*sample)).collect();
+                    sink.push(&samples);
+                },
+                |e| eprintln!("Audio input stream error: {}", e),
+                None,
+            )
+            .map_err(|e| format!("Failed to open input stream: {}", e))
+    }
+}
+
+impl InputDevice for SystemInputDevice {
+    fn info(&self) -> InputDeviceInfo {
+        self.info.clone()
+    }
+
+    fn format(&self) -> Result<AudioFormat, String> {
+        let config = self.config()?;
+        Ok(AudioFormat {
+            sample_rate: config.sample_rate().0,
+            channels: config.channels() as usize,
+        })
+    }
+
+    fn capture(&mut self, sink: &CaptureSink, stop: &Receiver<()>, started: &dyn Fn()) -> Result<(), String> {
+        let supported = self.config()?;
+        let config = supported.config();
+
+        let stream = match supported.sample_format() {
+            SampleFormat::F32 => self.build_stream::<f32>(&config, sink)?,
+            SampleFormat::I16 => self.build_stream::<i16>(&config, sink)?,
+            SampleFormat::I32 => self.build_stream::<i32>(&config, sink)?,
+            SampleFormat::U16 => self.build_stream::<u16>(&config, sink)?,
+            SampleFormat::U8 => self.build_stream::<u8>(&config, sink)?,
+            format => return Err(format!("Unsupported input sample format: {}", format)),
+        };
+        stream.play().map_err(|e| format!("Failed to start input stream: {}", e))?;
+        started();
+
+        // The stream delivers samples from its own thread until it is dropped
+        let _ = stop.recv();
+        Ok(())
+    }
+}
+
+/// Plays a WAV file into the sink in real time. Used for headless testing
+/// and on machines without a microphone.
+pub struct FileInputDevice {
+    path: PathBuf,
+}
+
+impl FileInputDevice {
+    pub fn new(path: PathBuf) -> Self {
+        FileInputDevice { path }
+    }
+
+    fn open(&self) -> Result<hound::WavReader<std::io::BufReader<std::fs::File>>, String> {
+        hound::WavReader::open(&self.path)
+            .map_err(|e| format!("Failed to open fake input {}: {}", self.path.display(), e))
+    }
+
+    fn read_samples(&self) -> Result<Vec<f32>, String> {
+        let mut reader = self.open()?;
+        let spec = reader.spec();
+        let samples: Result<Vec<f32>, hound::Error> = match spec.sample_format {
+            hound::SampleFormat::Float => reader.samples::<f32>().collect(),
+            hound::SampleFormat::Int => {
+                let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .samples::<i32>()
+                    .map(|sample| sample.map(|value| value as f32 / scale))
+                    .collect()
+            }
+        };
+        samples.map_err(|e| format!("Failed to read fake input: {}", e))
+    }
+}
+
+impl InputDevice for FileInputDevice {
+    fn info(&self) -> InputDeviceInfo {
+        let name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.display().to_string());
+        InputDeviceInfo {
+            id: format!("{}{}", FILE_DEVICE_PREFIX, self.path.display()),
+            name: format!("File: {}", name),
+            kind: InputDeviceKind::File,
+            is_default: false,
+        }
+    }
+
+    fn format(&self) -> Result<AudioFormat, String> {
+        let spec = self.open()?.spec();
+        Ok(AudioFormat {
+            sample_rate: spec.sample_rate,
+            channels: spec.channels as usize,
+        })
+    }
+
+    fn capture(&mut self, sink: &CaptureSink, stop: &Receiver<()>, started: &dyn Fn()) -> Result<(), String> {
+        let format = self.format()?;
+        let samples = self.read_samples()?;
+        let block = (format.sample_rate as u64 * FAKE_BLOCK_MS / 1000) as usize * format.channels;
+        started();
+
+        // Deliver the file block by block at its own pace, then behave like a silent microphone
+        let mut blocks = samples.chunks(block.max(format.channels));
+        loop {
+            match stop.recv_timeout(Duration::from_millis(FAKE_BLOCK_MS)) {
+                Err(RecvTimeoutError::Timeout) => match blocks.next() {
+                    Some(block) => sink.push(block),
+                    None => sink.end_of_input(),
+                },
+                _ => return Ok(()),
+            }
+        }
+    }
+}
+
+fn fake_input_paths() -> Vec<PathBuf> {
+    std::env::var_os(FAKE_INPUT_ENV)
+        .map(|paths| std::env::split_paths(&paths).collect())
+        .unwrap_or_default()
+}
+
+/// All capture devices: the host's microphones followed by the fake devices
+/// listed in `VOICE_INTELLIGENCE_FAKE_INPUT`
+pub fn input_devices() -> Vec<Box<dyn InputDevice>> {
+    list_devices(&fake_input_paths())
+}
+
+fn list_devices(fake_inputs: &[PathBuf]) -> Vec<Box<dyn InputDevice>> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|device| device.name().ok());
+
+    // Headless machines often have no audio host at all; the fake devices still work there
+    let host_devices: Vec<cpal::Device> = match host.input_devices() {
+        Ok(devices) => devices.collect(),
+        Err(e) => {
+            eprintln!("Failed to enumerate input devices: {}", e);
+            Vec::new()
+        }
+    };
+
+    let mut devices: Vec<Box<dyn InputDevice>> = Vec::new();
+    for device in host_devices {
+        let Ok(name) = device.name() else {
+            continue;
+        };
+        let info = InputDeviceInfo {
+            id: format!("{}{}", SYSTEM_DEVICE_PREFIX, name),
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+            kind: InputDeviceKind::System,
+        };
+        devices.push(Box::new(SystemInputDevice { device, info }));
+    }
+
+    for path in fake_inputs {
+        devices.push(Box::new(FileInputDevice::new(path.clone())));
+    }
+
+    devices
+}
+
+/// Open a device by ID, or the default microphone (falling back to the first
+/// fake device) when no ID is given. File devices can only be opened when
+/// their path is listed in `VOICE_INTELLIGENCE_FAKE_INPUT`.
+pub fn open_input_device(device_id: Option<&str>) -> Result<Box<dyn InputDevice>, String> {
+    open_device(device_id, &fake_input_paths())
+}
+
+fn open_device(device_id: Option<&str>, fake_inputs: &[PathBuf]) -> Result<Box<dyn InputDevice>, String> {
+    let mut devices = list_devices(fake_inputs);
+    let position = match device_id {
+        Some(id) => devices.iter().position(|device| device.info().id == id),
+        None => devices
+            .iter()
+            .position(|device| device.info().is_default)
+            .or_else(|| (!devices.is_empty()).then_some(0)),
+    };
+
+    match position {
+        Some(index) => Ok(devices.swap_remove(index)),
+        None => Err(match device_id {
+            Some(id) => format!("Input device not found: {}", id),
+            None => "No input device available".to_string(),
+        }),
+    }
+}
+
+struct ActiveCapture {
+    stop: Sender<()>,
+    thread: JoinHandle<Result<(), String>>,
+    sink: CaptureSink,
+    format: AudioFormat,
+    device_name: String,
+    started: Instant,
+}
+
+#[derive(Default)]
+struct CaptureState {
+    selected_device: Option<String>,
+    starting: bool, // A device is being opened; CAPTURE is not held meanwhile
+    active: Option<ActiveCapture>,
+    last_recording: Option<Vec<f32>>, // 16 kHz mono, ready for whisper
+}
+
+lazy_static::lazy_static! {
+    static ref CAPTURE: Mutex<CaptureState> = Mutex::new(CaptureState::default());
+}
+
+fn capture_status(state: &CaptureState) -> CaptureStatus {
+    CaptureStatus {
+        capturing: state.active.is_some(),
+        device_id: state.selected_device.clone(),
+        elapsed_ms: state
+            .active
+            .as_ref()
+            .map(|active| active.started.elapsed().as_millis() as u64),
+    }
+}
+
+fn emit_capture_state(app_handle: Option<&AppHandle>, status: CaptureStatus) {
+    if let Some(app) = app_handle {
+        let _ = app.emit("capture-state", status);
+    }
+}
+
+pub(crate) fn is_capturing() -> bool {
+    CAPTURE.lock().unwrap().active.is_some()
+}
+
+/// Start capturing from the selected device on a dedicated thread.
+/// Returns once the device delivers samples or failed to open.
+pub(crate) fn begin_capture(app_handle: &AppHandle) -> Result<(), String> {
+    start_capture_thread(Some(app_handle), fake_input_paths())
+}
+
+/// Stop the running capture and keep its audio, converted for whisper, as the last recording
+pub(crate) fn end_capture(app_handle: &AppHandle) -> Result<CapturedAudio, String> {
+    stop_capture_thread(Some(app_handle))
+}
+
+// Events are only emitted when an app handle is given
+fn start_capture_thread(app_handle: Option<&AppHandle>, fake_inputs: Vec<PathBuf>) -> Result<(), String> {
+    let device_id = {
+        let mut state = CAPTURE.lock().unwrap();
+        if state.active.is_some() || state.starting {
+            return Err("Audio capture is already running".to_string());
+        }
+        state.starting = true;
+        state.selected_device.clone()
+    };
+    let sink = CaptureSink::new(app_handle.cloned());
+    let (stop_sender, stop_receiver) = mpsc::channel();
+    let (ready_sender, ready_receiver) = mpsc::sync_channel(1);
+
+    let thread_sink = sink.clone();
+    let thread = std::thread::spawn(move || {
+        let opened = open_device(device_id.as_deref(), &fake_inputs)
+            .and_then(|device| device.format().map(|format| (device, format)));
+        let (mut device, format) = match opened {
+            Ok(opened) => opened,
+            Err(e) => {
+                let _ = ready_sender.send(Err(e.clone()));
+                return Err(e);
+            }
+        };
+
+        thread_sink.set_channels(format.channels);
+        let name = device.info().name;
+        let started = || {
+            let _ = ready_sender.send(Ok((format, name.clone())));
+        };
+        let result = device.capture(&thread_sink, &stop_receiver, &started);
+        if let Err(e) = &result {
+            let _ = ready_sender.try_send(Err(e.clone()));
+        }
+        result
+    });
+
+    let ready = ready_receiver
+        .recv()
+        .map_err(|_| "Audio capture thread exited unexpectedly".to_string())
+        .and_then(|ready| ready);
+
+    let mut state = CAPTURE.lock().unwrap();
+    state.starting = false;
+    let (format, device_name) = ready?;
+    state.active = Some(ActiveCapture {
+        stop: stop_sender,
+        thread,
+        sink,
+        format,
+        device_name,
+        started: Instant::now(),
+    });
+    emit_capture_state(app_handle, capture_status(&state));
+
+    Ok(())
+}
+
+fn stop_capture_thread(app_handle: Option<&AppHandle>) -> Result<CapturedAudio, String> {
+    let active = {
+        let mut state = CAPTURE.lock().unwrap();
+        let active = state.active.take().ok_or("Audio capture is not running")?;
+        emit_capture_state(app_handle, capture_status(&state));
+        active
+    };
+
+    let _ = active.stop.send(());
+    active
+        .thread
+        .join()
+        .map_err(|_| "Audio capture thread panicked".to_string())??;
+
+    let samples = condition_audio(&active.sink.take(), &active.format)?;
+    let duration_ms = (samples.len() as u64 * 1000) / WHISPER_SAMPLE_RATE as u64;
+    CAPTURE.lock().unwrap().last_recording = Some(samples);
+
+    Ok(CapturedAudio {
+        device_name: active.device_name,
+        duration_ms,
+    })
+}
+
+/// Hand the last finished recording to the caller, leaving none behind
+pub(crate) fn take_last_recording() -> Option<Vec<f32>> {
+    CAPTURE.lock().unwrap().last_recording.take()
+}
+
+#[tauri::command]
+pub async fn list_input_devices() -> Result<Vec<InputDeviceInfo>, String> {
+    tauri::async_runtime::spawn_blocking(|| input_devices().iter().map(|device| device.info()).collect::<Vec<_>>())
+        .await
+        .map_err(|e| format!("Device enumeration task failed: {}", e))
+}
+
+/// Select the device used by the next capture; None selects the system default
+#[tauri::command]
+pub async fn select_input_device(device_id: Option<String>, app_handle: AppHandle) -> Result<(), String> {
+    if let Some(id) = &device_id {
+        let lookup = id.clone();
+        tauri::async_runtime::spawn_blocking(move || open_input_device(Some(&lookup)).map(|_| ()))
+            .await
+            .map_err(|e| format!("Device lookup task failed: {}", e))??;
+    }
+
+    let mut state = CAPTURE.lock().unwrap();
+    state.selected_device = device_id;
+    emit_capture_state(Some(&app_handle), capture_status(&state));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_capture_status() -> Result<CaptureStatus, String> {
+    Ok(capture_status(&CAPTURE.lock().unwrap()))
+}
+
+/// Start recording from the selected input device. Levels are reported via
+/// `audio-level` events while the capture runs.
+#[tauri::command]
+pub async fn start_capture(app_handle: AppHandle) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || begin_capture(&app_handle))
+        .await
+        .map_err(|e| format!("Capture task failed: {}", e))?
+}
+
+/// Stop recording. The audio is kept in the backend for `transcribe_last_capture`.
+#[tauri::command]
+pub async fn stop_capture(app_handle: AppHandle) -> Result<CapturedAudio, String> {
+    tauri::async_runtime::spawn_blocking(move || end_capture(&app_handle))
+        .await
+        .map_err(|e| format!("Capture task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    // Half a second of a 440 Hz tone as 48 kHz stereo, so capture has to downmix and resample
+    fn write_fixture(path: &Path) {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 48_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for n in 0..24_000 {
+            let phase = n as f32 * 440.0 * 2.0 * std::f32::consts::PI / 48_000.0;
+            let sample = (phase.sin() * 0.5 * i16::MAX as f32) as i16;
+            writer.write_sample(sample).unwrap();
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    // Tests that start captures share the global capture state
+    static CAPTURE_TESTS: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    /// Wait until the running fake device has played its whole file
+    fn wait_for_end_of_input() {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let exhausted = CAPTURE.lock().unwrap().active.as_ref().is_some_and(|active| active.sink.is_exhausted());
+            if exhausted {
+                return;
+            }
+            assert!(Instant::now() < deadline, "Fake input never reached the end of its file");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn captures_fake_input_file() {
+        let _serial = CAPTURE_TESTS.lock().unwrap_or_else(|e| e.into_inner());
+        let path = std::env::temp_dir().join(format!("voice-intelligence-capture-{}.wav", std::process::id()));
+        write_fixture(&path);
+        CAPTURE.lock().unwrap().selected_device = Some(format!("{}{}", FILE_DEVICE_PREFIX, path.display()));
+
+        start_capture_thread(None, vec![path.clone()]).unwrap();
+        assert!(is_capturing());
+        assert!(start_capture_thread(None, vec![path.clone()]).is_err());
+
+        wait_for_end_of_input();
+        let captured = stop_capture_thread(None).unwrap();
+        CAPTURE.lock().unwrap().selected_device = None;
+        let _ = std::fs::remove_file(&path);
+
+        assert!(!is_capturing());
+        assert!(captured.device_name.starts_with("File: voice-intelligence-capture-"));
+        assert_eq!(captured.duration_ms, 500);
+
+        let audio = take_last_recording().unwrap();
+        assert_eq!(audio.len(), WHISPER_SAMPLE_RATE as usize / 2);
+        assert!(audio.iter().any(|sample| sample.abs() > 0.5));
+    }
+
+    #[test]
+    fn file_devices_must_be_listed() {
+        let result = open_device(Some("file:/etc/hostname"), &[]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn fake_devices_are_listed_without_audio_host() {
+        let path = PathBuf::from("/tmp/fake-microphone.wav");
+        let devices = list_devices(&[path.clone()]);
+
+        let fake = devices.last().unwrap().info();
+        assert_eq!(fake.id, format!("{}{}", FILE_DEVICE_PREFIX, path.display()));
+        assert_eq!(fake.kind, InputDeviceKind::File);
+    }
+}
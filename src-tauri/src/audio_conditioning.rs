@@ -0,0 +1,266 @@
+use rubato::{FftFixedIn, Resampler};
+use serde::{Deserialize, Serialize};
+
+// Whisper's expected input sample rate
+pub const WHISPER_SAMPLE_RATE: u32 = 16_000;
+
+// Frames fed to the resampler per call
+const RESAMPLE_CHUNK: usize = 1_024;
+
+// Cutoff of the DC blocking filter, well below the lowest speech frequencies
+const DC_BLOCK_CUTOFF_HZ: f32 = 10.0;
+
+// Peak level after normalization, and the most gain normalization may apply (+20 dB)
+const NORMALIZE_PEAK: f32 = 0.95;
+const NORMALIZE_MAX_GAIN: f32 = 10.0;
+
+const MIN_SAMPLE_RATE: u32 = 8_000;
+const MAX_SAMPLE_RATE: u32 = 384_000;
+const MAX_CHANNELS: usize = 32;
+
+/// Layout of raw PCM handed to the backend. Samples are interleaved f32
+/// when there is more than one channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioFormat {
+    pub sample_rate: u32,
+    pub channels: usize,
+}
+
+impl Default for AudioFormat {
+    fn default() -> Self {
+        AudioFormat {
+            sample_rate: WHISPER_SAMPLE_RATE,
+            channels: 1,
+        }
+    }
+}
+
+impl AudioFormat {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_SAMPLE_RATE..=MAX_SAMPLE_RATE).contains(&self.sample_rate) {
+            return Err(format!(
+                "Unsupported sample rate {} Hz (expected {}-{} Hz)",
+                self.sample_rate, MIN_SAMPLE_RATE, MAX_SAMPLE_RATE
+            ));
+        }
+        if self.channels == 0 || self.channels > MAX_CHANNELS {
+            return Err(format!("Unsupported channel count: {}", self.channels));
+        }
+        Ok(())
+    }
+}
+
+/// Convert PCM in the declared format into whisper input: mono, 16 kHz,
+/// without DC offset and peak normalized.
+pub fn condition_audio(samples: &[f32], format: &AudioFormat) -> Result<Vec<f32>, String> {
+    format.validate()?;
+
+    if samples.len() % format.channels != 0 {
+        return Err(format!(
+            "Sample count {} is not a multiple of the channel count {}",
+            samples.len(),
+            format.channels
+        ));
+    }
+    if samples.iter().any(|sample| !sample.is_finite()) {
+        return Err("Audio contains invalid samples".to_string());
+    }
+
+    let mut mono = downmix(samples, format.channels);
+    remove_dc_offset(&mut mono, format.sample_rate);
+    let mut output = resample(&mono, format.sample_rate, WHISPER_SAMPLE_RATE)?;
+    normalize_peak(&mut output);
+
+    Ok(output)
+}
+
+/// Average interleaved channels into a single mono channel
+pub fn downmix(samples: &[f32], channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// One-pole high-pass filter that removes DC offset and slow drift. The pole
+/// is derived from the sample rate so the cutoff stays at `DC_BLOCK_CUTOFF_HZ`.
+pub fn remove_dc_offset(samples: &mut [f32], sample_rate: u32) {
+    let Some(&first) = samples.first() else {
+        return;
+    };
+    let pole = (-2.0 * std::f32::consts::PI * DC_BLOCK_CUTOFF_HZ / sample_rate as f32).exp();
+
+    // Start from the first sample so a constant offset doesn't produce a step
+    let mut previous_input = first;
+    let mut previous_output = 0.0f32;
+    for sample in samples.iter_mut() {
+        let input = *sample;
+        previous_output = input - previous_input + pole * previous_output;
+        previous_input = input;
+        *sample = previous_output;
+    }
+}
+
+/// Band-limited FFT resampling; the anti-aliasing filter is part of the resampler
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>, String> {
+    if from_rate == to_rate || samples.is_empty() {
+        return Ok(samples.to_vec());
+    }
+
+    let mut resampler = FftFixedIn::<f32>::new(
+        from_rate as usize,
+        to_rate as usize,
+        RESAMPLE_CHUNK,
+        2,
+        1,
+    )
+    .map_err(|e| format!("Failed to create resampler: {}", e))?;
+
+    let delay = resampler.output_delay();
+    let expected = (samples.len() as u64 * to_rate as u64 / from_rate as u64) as usize;
+    let mut output = Vec::with_capacity(expected + delay);
+    let mut position = 0;
+
+    while position + resampler.input_frames_next() <= samples.len() {
+        let frames = resampler.input_frames_next();
+        let chunk = resampler
+            .process(&[&samples[position..position + frames]], None)
+            .map_err(|e| format!("Resampling failed: {}", e))?;
+        output.extend_from_slice(&chunk[0]);
+        position += frames;
+    }
+
+    if position < samples.len() {
+        let chunk = resampler
+            .process_partial(Some(&[&samples[position..]]), None)
+            .map_err(|e| format!("Resampling failed: {}", e))?;
+        output.extend_from_slice(&chunk[0]);
+    }
+
+    // Flush the frames still held back by the filter delay
+    while output.len() < expected + delay {
+        let chunk = resampler
+            .process_partial::<&[f32]>(None, None)
+            .map_err(|e| format!("Resampling failed: {}", e))?;
+        if chunk[0].is_empty() {
+            break;
+        }
+        output.extend_from_slice(&chunk[0]);
+    }
+
+    output.drain(..delay.min(output.len()));
+    output.truncate(expected);
+    Ok(output)
+}
+
+/// Scale so the loudest sample reaches `NORMALIZE_PEAK`, with bounded gain
+/// so near-silent recordings are not blown up into noise
+pub fn normalize_peak(samples: &mut [f32]) {
+    let peak = samples.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+    if peak <= f32::EPSILON {
+        return;
+    }
+
+    let gain = (NORMALIZE_PEAK / peak).min(NORMALIZE_MAX_GAIN);
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frequency: f32, amplitude: f32, sample_rate: u32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                amplitude * (2.0 * std::f32::consts::PI * frequency * t).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn resampled_length_follows_the_rate_ratio() {
+        let cases = [(48_000, 48_000), (44_100, 44_100), (44_100, 100_123), (8_000, 999)];
+        for (from_rate, len) in cases {
+            let input = sine(440.0, 0.5, from_rate, len);
+            let output = resample(&input, from_rate, WHISPER_SAMPLE_RATE).unwrap();
+            let expected = len as u64 * WHISPER_SAMPLE_RATE as u64 / from_rate as u64;
+            assert_eq!(output.len() as u64, expected, "{} samples at {} Hz", len, from_rate);
+        }
+    }
+
+    #[test]
+    fn resampling_keeps_the_signal_level() {
+        let output = resample(&sine(440.0, 0.5, 48_000, 48_000), 48_000, WHISPER_SAMPLE_RATE).unwrap();
+        let peak = output[1_000..15_000].iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        assert!((peak - 0.5).abs() < 0.02, "peak {}", peak);
+    }
+
+    #[test]
+    fn dc_blocker_removes_a_constant_offset() {
+        let mut samples: Vec<f32> = sine(440.0, 0.5, 16_000, 16_000)
+            .iter()
+            .map(|sample| sample + 0.25)
+            .collect();
+
+        remove_dc_offset(&mut samples, 16_000);
+
+        // Skip the filter's settling time
+        let tail = &samples[8_000..];
+        let mean = tail.iter().sum::<f32>() / tail.len() as f32;
+        assert!(mean.abs() < 0.01, "mean {}", mean);
+    }
+
+    #[test]
+    fn dc_blocker_leaves_constant_input_silent() {
+        let mut samples = vec![0.3; 1_000];
+        remove_dc_offset(&mut samples, 16_000);
+        assert!(samples.iter().all(|sample| *sample == 0.0));
+    }
+
+    #[test]
+    fn normalized_audio_is_left_unchanged() {
+        let original = sine(440.0, NORMALIZE_PEAK, 16_000, 1_600);
+        let peak = original.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        let mut samples = original.clone();
+
+        normalize_peak(&mut samples);
+
+        // Only the sine's sampled peak may fall slightly short of NORMALIZE_PEAK
+        for (normalized, original) in samples.iter().zip(&original) {
+            assert!((normalized - original * NORMALIZE_PEAK / peak).abs() < 1e-6);
+            assert!((normalized - original).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn normalization_gain_is_bounded() {
+        let mut quiet = vec![0.001, -0.001];
+        normalize_peak(&mut quiet);
+        assert_eq!(quiet, vec![0.001 * NORMALIZE_MAX_GAIN, -0.001 * NORMALIZE_MAX_GAIN]);
+
+        let mut silence = vec![0.0; 16];
+        normalize_peak(&mut silence);
+        assert!(silence.iter().all(|sample| *sample == 0.0));
+    }
+
+    #[test]
+    fn stereo_is_downmixed_and_conditioned() {
+        let stereo: Vec<f32> = sine(440.0, 0.5, 48_000, 4_800)
+            .into_iter()
+            .flat_map(|sample| [sample, sample])
+            .collect();
+        let format = AudioFormat { sample_rate: 48_000, channels: 2 };
+
+        let output = condition_audio(&stereo, &format).unwrap();
+
+        assert_eq!(output.len(), 1_600);
+        assert!(condition_audio(&stereo[1..], &format).is_err());
+    }
+}
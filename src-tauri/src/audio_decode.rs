@@ -0,0 +1,186 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use audiopus::coder::Decoder as OpusDecoder;
+use audiopus::packet::Packet as OpusPacket;
+use audiopus::{Channels as OpusChannels, MutSignals, SampleRate as OpusSampleRate};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CodecParameters, DecoderOptions, CODEC_TYPE_NULL, CODEC_TYPE_OPUS};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tauri::AppHandle;
+
+use crate::audio_conditioning::{condition_audio, AudioFormat};
+use crate::transcription_jobs::{transcribe_queued, TranscriptionPriority};
+use crate::whisper::{ModelVariant, TranscriptionOptions, TranscriptionResult};
+
+// Largest Opus frame (120 ms at 48 kHz), per channel
+const OPUS_MAX_FRAME_SAMPLES: usize = 5_760;
+
+/// Interleaved PCM decoded from an audio file
+pub struct DecodedAudio {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: usize,
+}
+
+/// Decode WAV, FLAC, MP3, Ogg/Vorbis or Ogg/Opus into interleaved f32 samples
+pub fn decode_audio_file(path: &Path) -> Result<DecodedAudio, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open audio file: {}", e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    // The extension only speeds up probing; the container is detected from its contents
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| format!("Unsupported audio format: {}", e))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or("Audio file contains no audio track")?;
+    let track_id = track.id;
+    let params = track.codec_params.clone();
+
+    // Symphonia demuxes Ogg/Opus but has no Opus decoder
+    if params.codec == CODEC_TYPE_OPUS {
+        return decode_opus(format.as_mut(), track_id, &params);
+    }
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported audio codec: {}", e))?;
+
+    let mut samples = Vec::new();
+    let mut sample_rate = params.sample_rate.unwrap_or(0);
+    let mut channels = params.channels.map(|channels| channels.count()).unwrap_or(0);
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("Failed to read audio file: {}", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt frame is skipped rather than failing the whole file
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(format!("Failed to decode audio: {}", e)),
+        };
+
+        let spec = *decoded.spec();
+        sample_rate = spec.rate;
+        channels = spec.channels.count();
+
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buffer.samples());
+    }
+
+    if sample_rate == 0 || channels == 0 {
+        return Err("Audio file contains no decodable audio".to_string());
+    }
+
+    Ok(DecodedAudio { samples, sample_rate, channels })
+}
+
+fn decode_opus(
+    format: &mut dyn FormatReader,
+    track_id: u32,
+    params: &CodecParameters,
+) -> Result<DecodedAudio, String> {
+    let channels = params.channels.map(|channels| channels.count()).unwrap_or(1);
+    let opus_channels = match channels {
+        1 => OpusChannels::Mono,
+        2 => OpusChannels::Stereo,
+        _ => return Err(format!("Unsupported Opus channel count: {}", channels)),
+    };
+
+    let mut decoder = OpusDecoder::new(OpusSampleRate::Hz48000, opus_channels)
+        .map_err(|e| format!("Failed to create Opus decoder: {}", e))?;
+
+    let mut samples = Vec::new();
+    let mut frame = vec![0.0f32; OPUS_MAX_FRAME_SAMPLES * channels];
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(format!("Failed to read audio file: {}", e)),
+        };
+        if packet.track_id() != track_id || packet.data.is_empty() {
+            continue;
+        }
+
+        let input = OpusPacket::try_from(&packet.data[..])
+            .map_err(|e| format!("Invalid Opus packet: {}", e))?;
+        let output = MutSignals::try_from(&mut frame[..])
+            .map_err(|e| format!("Invalid Opus buffer: {}", e))?;
+        let decoded = decoder
+            .decode_float(Some(input), output, false)
+            .map_err(|e| format!("Failed to decode audio: {}", e))?;
+
+        samples.extend_from_slice(&frame[..decoded * channels]);
+    }
+
+    // Drop the encoder priming samples announced in the Opus header
+    let pre_skip = params.delay.unwrap_or(0) as usize * channels;
+    samples.drain(..pre_skip.min(samples.len()));
+
+    Ok(DecodedAudio { samples, sample_rate: 48_000, channels })
+}
+
+/// Decode an audio file into whisper's 16 kHz mono input
+pub fn load_audio_for_whisper(path: &Path) -> Result<Vec<f32>, String> {
+    let decoded = decode_audio_file(path)?;
+    let format = AudioFormat {
+        sample_rate: decoded.sample_rate,
+        channels: decoded.channels,
+    };
+    condition_audio(&decoded.samples, &format)
+}
+
+/// Transcribe an audio file from disk. The file is decoded in the backend,
+/// so long recordings never have to cross the IPC bridge.
+#[tauri::command]
+pub async fn transcribe_file(
+    path: String,
+    variant: ModelVariant,
+    options: Option<TranscriptionOptions>,
+    app_handle: AppHandle,
+) -> Result<TranscriptionResult, String> {
+    let path = PathBuf::from(path);
+    if !path.is_file() {
+        return Err(format!("Audio file not found: {}", path.display()));
+    }
+
+    let audio_data = tauri::async_runtime::spawn_blocking(move || load_audio_for_whisper(&path))
+        .await
+        .map_err(|e| format!("Audio decoding task failed: {}", e))??;
+
+    if audio_data.is_empty() {
+        return Err("Audio file contains no samples".to_string());
+    }
+
+    transcribe_queued(
+        audio_data,
+        variant,
+        options.unwrap_or_default(),
+        TranscriptionPriority::Batch,
+        app_handle,
+    )
+    .await
+}
@@ -0,0 +1,633 @@
+use once_cell::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use crate::file_utils::{calculate_file_checksum, models_directory};
+use crate::model_catalog::{LanguageSupport, ModelFamily, ModelSize, Quantization};
+use crate::model_pool::unload_whisper_model;
+use crate::whisper::ModelVariant;
+
+const REGISTRY_FILE: &str = "custom-models.json";
+pub(crate) const CUSTOM_ID_PREFIX: &str = "custom-";
+
+const GGML_MAGIC: u32 = 0x6767_6d6c; // "ggml" read as a little-endian u32
+const GGUF_MAGIC: u32 = 0x4655_4747; // "GGUF"
+// whisper.cpp stores the quantization version in ftype, scaled by this
+const GGML_QNT_VERSION_FACTOR: i32 = 1000;
+
+// Whisper's tokenizer: 51864 tokens for English-only models, one more for
+// multilingual ones and two more since large-v3
+const ENGLISH_VOCAB_SIZE: i32 = 51_864;
+const MAX_VOCAB_SIZE: i32 = 51_866;
+const MAX_TOKEN_LENGTH: u32 = 1_024;
+
+const MB: u64 = 1024 * 1024;
+
+/// Hyperparameters from the header of a whisper.cpp ggml model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelHeader {
+    pub n_vocab: i32,
+    pub n_audio_ctx: i32,
+    pub n_audio_state: i32,
+    pub n_audio_head: i32,
+    pub n_audio_layer: i32,
+    pub n_text_ctx: i32,
+    pub n_text_state: i32,
+    pub n_text_head: i32,
+    pub n_text_layer: i32,
+    pub n_mels: i32,
+    pub ftype: i32, // Weight type, without the quantization version
+}
+
+impl ModelHeader {
+    fn validate(&self) -> Result<(), String> {
+        if !(ENGLISH_VOCAB_SIZE..=MAX_VOCAB_SIZE).contains(&self.n_vocab) {
+            return Err(format!("Vocabulary size {} is not a Whisper vocabulary", self.n_vocab));
+        }
+        if self.n_mels != 80 && self.n_mels != 128 {
+            return Err(format!("Unsupported number of mel bands: {}", self.n_mels));
+        }
+        for (name, ctx) in [("audio", self.n_audio_ctx), ("text", self.n_text_ctx)] {
+            if !(1..=4096).contains(&ctx) {
+                return Err(format!("Invalid {} context length: {}", name, ctx));
+            }
+        }
+        for (name, state, heads, layers) in [
+            ("audio", self.n_audio_state, self.n_audio_head, self.n_audio_layer),
+            ("text", self.n_text_state, self.n_text_head, self.n_text_layer),
+        ] {
+            if !(1..=64).contains(&heads) || !(1..=8192).contains(&state) || state % heads != 0 {
+                return Err(format!("Invalid {} attention shape: {} dimensions, {} heads", name, state, heads));
+            }
+            if !(1..=64).contains(&layers) {
+                return Err(format!("Invalid number of {} layers: {}", name, layers));
+            }
+        }
+        self.quantization().map(|_| ())
+    }
+
+    fn quantization(&self) -> Result<Quantization, String> {
+        // ggml_ftype values (not ggml_type ids), as written by whisper.cpp's
+        // conversion and quantize tools
+        match self.ftype {
+            0 => Ok(Quantization::F32),
+            1 => Ok(Quantization::F16),
+            2 => Ok(Quantization::Q4_0),
+            3 => Ok(Quantization::Q4_1),
+            7 => Ok(Quantization::Q8_0),
+            8 => Ok(Quantization::Q5_0),
+            9 => Ok(Quantization::Q5_1),
+            other => Err(format!("Unsupported weight type: {}", other)),
+        }
+    }
+
+    fn languages(&self) -> LanguageSupport {
+        if self.n_vocab > ENGLISH_VOCAB_SIZE {
+            LanguageSupport::Multilingual
+        } else {
+            LanguageSupport::EnglishOnly
+        }
+    }
+
+    /// Size class of the architecture, judged by the encoder depth
+    fn model_size(&self) -> Option<ModelSize> {
+        match self.n_audio_layer {
+            4 => Some(ModelSize::Tiny),
+            6 => Some(ModelSize::Base),
+            12 => Some(ModelSize::Small),
+            24 => Some(ModelSize::Medium),
+            32 => Some(ModelSize::Large),
+            _ => None,
+        }
+    }
+
+    fn family(&self) -> ModelFamily {
+        match (self.n_audio_layer, self.n_text_layer) {
+            (32, 4) => ModelFamily::Turbo,
+            (_, 2) => ModelFamily::Distil,
+            _ => ModelFamily::Whisper,
+        }
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, String> {
+    let mut bytes = [0u8; 4];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(|e| format!("Model file is truncated: {}", e))?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_i32(reader: &mut impl Read) -> Result<i32, String> {
+    read_u32(reader).map(|value| value as i32)
+}
+
+fn skip_bytes(reader: &mut impl Read, count: u64) -> Result<(), String> {
+    let skipped = io::copy(&mut reader.take(count), &mut io::sink())
+        .map_err(|e| format!("Failed to read model file: {}", e))?;
+    if skipped != count {
+        return Err("Model file is truncated".to_string());
+    }
+    Ok(())
+}
+
+/// Read and check the header of a whisper.cpp model: magic, hyperparameters,
+/// mel filters and vocabulary. Tensors are left to whisper.cpp.
+pub fn read_model_header(path: &Path) -> Result<ModelHeader, String> {
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open model file: {}", e))?;
+    let mut reader = BufReader::new(file);
+
+    match read_u32(&mut reader)? {
+        GGML_MAGIC => {}
+        GGUF_MAGIC => {
+            return Err("GGUF model files are not supported; import a whisper.cpp ggml model instead".to_string());
+        }
+        magic => return Err(format!("Not a ggml model file (magic 0x{:08x})", magic)),
+    }
+
+    let mut header = ModelHeader {
+        n_vocab: read_i32(&mut reader)?,
+        n_audio_ctx: read_i32(&mut reader)?,
+        n_audio_state: read_i32(&mut reader)?,
+        n_audio_head: read_i32(&mut reader)?,
+        n_audio_layer: read_i32(&mut reader)?,
+        n_text_ctx: read_i32(&mut reader)?,
+        n_text_state: read_i32(&mut reader)?,
+        n_text_head: read_i32(&mut reader)?,
+        n_text_layer: read_i32(&mut reader)?,
+        n_mels: read_i32(&mut reader)?,
+        ftype: read_i32(&mut reader)?,
+    };
+    header.ftype %= GGML_QNT_VERSION_FACTOR;
+    header.validate()?;
+
+    let filter_mels = read_i32(&mut reader)?;
+    let filter_fft = read_i32(&mut reader)?;
+    if filter_mels != header.n_mels || !(1..=1024).contains(&filter_fft) {
+        return Err(format!("Invalid mel filter bank: {} x {}", filter_mels, filter_fft));
+    }
+    skip_bytes(&mut reader, filter_mels as u64 * filter_fft as u64 * 4)?;
+
+    // Special tokens may be left out of the file; whisper.cpp adds them
+    let stored_tokens = read_i32(&mut reader)?;
+    if !(1..=header.n_vocab).contains(&stored_tokens) {
+        return Err(format!(
+            "Vocabulary has {} tokens, expected at most {}",
+            stored_tokens, header.n_vocab
+        ));
+    }
+    for index in 0..stored_tokens {
+        let length = read_u32(&mut reader)?;
+        if length > MAX_TOKEN_LENGTH {
+            return Err(format!("Vocabulary token {} is {} bytes long", index, length));
+        }
+        skip_bytes(&mut reader, length as u64)?;
+    }
+
+    Ok(header)
+}
+
+/// A model imported by the user, selectable as `ModelVariant::Custom`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomModel {
+    pub variant: ModelVariant,
+    pub name: String,
+    pub filename: String,    // File in the models directory
+    pub source_path: String, // Where the model was imported from
+    pub linked: bool,        // The file links to source_path instead of being a copy
+    pub imported_at: i64,    // Unix time in milliseconds
+    pub size: u64,           // bytes
+    pub sha256: String,
+    pub header: ModelHeader,
+    pub family: ModelFamily,
+    pub model_size: Option<ModelSize>, // None for non-standard architectures
+    pub quantization: Quantization,
+    pub languages: LanguageSupport,
+    pub memory_requirement: u64, // Model plus one inference state, in bytes
+}
+
+lazy_static::lazy_static! {
+    // Loaded from the registry file at startup
+    static ref CUSTOM_MODELS: Mutex<Vec<CustomModel>> = Mutex::new(Vec::new());
+    // IDs claimed by imports that are still copying or hashing. Lock after CUSTOM_MODELS.
+    static ref RESERVED_IDS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/// An ID held for an import in progress; released when dropped
+struct ReservedId(String);
+
+impl Drop for ReservedId {
+    fn drop(&mut self) {
+        RESERVED_IDS.lock().unwrap().remove(&self.0);
+    }
+}
+
+/// File name of a custom model in the models directory
+pub fn custom_model_filename(id: &str) -> String {
+    format!("{}.bin", id)
+}
+
+/// Registered custom model with the given ID
+pub fn custom_model(id: &str) -> Option<CustomModel> {
+    CUSTOM_MODELS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|model| matches!(&model.variant, ModelVariant::Custom(model_id) if model_id == id))
+        .cloned()
+}
+
+fn registry_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(models_directory(app_handle)?.join(REGISTRY_FILE))
+}
+
+fn save_registry(app_handle: &AppHandle, models: &[CustomModel]) -> Result<(), String> {
+    let path = registry_path(app_handle)?;
+    let content = serde_json::to_string_pretty(models)
+        .map_err(|e| format!("Failed to serialize custom models: {}", e))?;
+
+    // Write a temporary file first so a crash can't leave a truncated registry
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, content)
+        .and_then(|_| fs::rename(&temp, &path))
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp);
+            format!("Failed to save custom models: {}", e)
+        })
+}
+
+/// Read the registry file. A missing file is an empty registry; an unreadable
+/// one is moved aside to `.bak` so it is not overwritten by the next import.
+fn load_registry(path: &Path) -> Result<Vec<CustomModel>, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read custom models: {}", e)),
+    };
+
+    serde_json::from_str(&content).map_err(|e| {
+        let backup = path.with_extension("json.bak");
+        match fs::rename(path, &backup) {
+            Ok(()) => format!("Custom model registry is corrupt, moved to {}: {}", backup.display(), e),
+            Err(rename_error) => format!("Custom model registry is corrupt ({}) and could not be moved aside: {}", e, rename_error),
+        }
+    })
+}
+
+/// Load the registry of imported models. Entries whose file is gone are
+/// dropped, and model files without an entry are deleted.
+pub fn init_custom_models(app_handle: &AppHandle) {
+    let Ok(directory) = models_directory(app_handle) else {
+        return;
+    };
+    match load_and_clean(&directory) {
+        Ok((kept, dropped)) => {
+            if dropped {
+                if let Err(e) = save_registry(app_handle, &kept) {
+                    eprintln!("{}", e);
+                }
+            }
+            *CUSTOM_MODELS.lock().unwrap() = kept;
+        }
+        Err(e) => eprintln!("{}; custom model files were left in place", e),
+    }
+}
+
+/// Read the registry in `directory`, drop entries whose file is gone and
+/// delete model files that no entry refers to. Returns the kept entries and
+/// whether any were dropped. Nothing is deleted when the registry fails to
+/// load, since every file would look unregistered.
+fn load_and_clean(directory: &Path) -> Result<(Vec<CustomModel>, bool), String> {
+    let models = load_registry(&directory.join(REGISTRY_FILE))?;
+
+    let (kept, dropped): (Vec<CustomModel>, Vec<CustomModel>) = models
+        .into_iter()
+        .partition(|model| directory.join(&model.filename).exists());
+
+    for model in &dropped {
+        // A link whose source was deleted still has to go
+        let _ = fs::remove_file(directory.join(&model.filename));
+    }
+
+    // Files of imports that crashed before they were registered. While a
+    // backup of a corrupt registry exists they may still belong to it.
+    let backup = directory.join(REGISTRY_FILE).with_extension("json.bak");
+    if let (false, Ok(entries)) = (backup.exists(), fs::read_dir(directory)) {
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let orphan = file_name.starts_with(CUSTOM_ID_PREFIX)
+                && file_name.ends_with(".bin")
+                && !kept.iter().any(|model| model.filename == file_name);
+            if orphan {
+                eprintln!("Removing unregistered custom model file {}", file_name);
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    Ok((kept, !dropped.is_empty()))
+}
+
+/// ID for a new custom model: "custom-" and a slug of its name, made unique
+fn unique_id(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    let mut slug = String::new();
+    for c in name.trim().to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    let base = format!("{}{}", CUSTOM_ID_PREFIX, if slug.is_empty() { "model" } else { slug });
+
+    let mut id = base.clone();
+    let mut suffix = 2;
+    while taken(&id) {
+        id = format!("{}-{}", base, suffix);
+        suffix += 1;
+    }
+    id
+}
+
+/// Claim a unique ID for an import, skipping registered models, other
+/// imports in progress and files already in the models directory
+fn reserve_id(name: &str, directory: &Path) -> ReservedId {
+    let models = CUSTOM_MODELS.lock().unwrap();
+    let mut reserved = RESERVED_IDS.lock().unwrap();
+
+    let id = unique_id(name, |id| {
+        reserved.contains(id)
+            || models
+                .iter()
+                .any(|model| matches!(&model.variant, ModelVariant::Custom(model_id) if model_id == id))
+            || directory.join(custom_model_filename(id)).symlink_metadata().is_ok()
+    });
+    reserved.insert(id.clone());
+    ReservedId(id)
+}
+
+/// Copy `source` to `target`, failing if `target` already exists
+fn copy_model_file(source: &Path, target: &Path) -> Result<(), String> {
+    let mut input = fs::File::open(source).map_err(|e| format!("Failed to open model file: {}", e))?;
+    let mut output = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(target)
+        .map_err(|e| format!("Failed to create model file: {}", e))?;
+
+    io::copy(&mut input, &mut output)
+        .and_then(|_| output.sync_all())
+        .map_err(|e| {
+            // A failed copy leaves nothing behind
+            let _ = fs::remove_file(target);
+            format!("Failed to copy model file: {}", e)
+        })
+}
+
+/// Place `source` at `target` without copying: a symbolic link where the
+/// platform allows it, otherwise a hard link
+fn link_model_file(source: &Path, target: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    let linked = std::os::unix::fs::symlink(source, target);
+    #[cfg(windows)]
+    let linked = std::os::windows::fs::symlink_file(source, target);
+    #[cfg(not(any(unix, windows)))]
+    let linked: io::Result<()> = Err(io::Error::new(io::ErrorKind::Unsupported, "symbolic links"));
+
+    linked
+        .or_else(|_| fs::hard_link(source, target))
+        .map_err(|e| format!("Failed to link model file: {}", e))
+}
+
+/// Copy or link a ggml model into the models directory after validating its
+/// header, and register it as a custom model variant. GGUF files are rejected:
+/// whisper.cpp only loads its own ggml format.
+#[tauri::command]
+pub async fn import_model(
+    app_handle: AppHandle,
+    path: String,
+    name: Option<String>,
+    link: bool,
+) -> Result<CustomModel, String> {
+    let source = fs::canonicalize(&path).map_err(|e| format!("Model file not found: {}", e))?;
+    let name = name
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| source.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "Custom model".to_string());
+
+    let directory = models_directory(&app_handle)?;
+    let reservation = reserve_id(&name, &directory);
+    let id = reservation.0.clone();
+    let filename = custom_model_filename(&id);
+    let target = directory.join(&filename);
+
+    let header_source = source.clone();
+    let header_target = target.clone();
+    let (header, size) = tauri::async_runtime::spawn_blocking(move || {
+        let header = read_model_header(&header_source)?;
+        // Neither links nor copies replace an existing file
+        if link {
+            link_model_file(&header_source, &header_target)?;
+        } else {
+            copy_model_file(&header_source, &header_target)?;
+        }
+        let size = fs::metadata(&header_target).map(|metadata| metadata.len()).unwrap_or(0);
+        Ok::<_, String>((header, size))
+    })
+    .await
+    .map_err(|e| format!("Import task failed: {}", e))??;
+
+    let sha256 = match calculate_file_checksum(target.to_string_lossy().to_string()).await {
+        Ok(sha256) => sha256,
+        Err(e) => {
+            let _ = fs::remove_file(&target);
+            return Err(e);
+        }
+    };
+
+    let model = CustomModel {
+        variant: ModelVariant::Custom(id),
+        name,
+        filename,
+        source_path: source.to_string_lossy().to_string(),
+        linked: link,
+        imported_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0),
+        size,
+        sha256,
+        family: header.family(),
+        model_size: header.model_size(),
+        quantization: header.quantization()?,
+        languages: header.languages(),
+        // Weights plus roughly what whisper.cpp allocates for one state
+        memory_requirement: size + size / 5 + 100 * MB,
+        header,
+    };
+
+    let mut models = CUSTOM_MODELS.lock().unwrap();
+    models.push(model.clone());
+    if let Err(e) = save_registry(&app_handle, &models) {
+        models.pop();
+        let _ = fs::remove_file(&target);
+        return Err(e);
+    }
+    // The registry entry holds the ID from here on
+    drop(models);
+    drop(reservation);
+
+    Ok(model)
+}
+
+/// Imported models, oldest first
+#[tauri::command]
+pub async fn list_custom_models() -> Result<Vec<CustomModel>, String> {
+    Ok(CUSTOM_MODELS.lock().unwrap().clone())
+}
+
+/// Unload and forget a custom model. Linked source files are left alone.
+#[tauri::command]
+pub async fn remove_custom_model(app_handle: AppHandle, variant: ModelVariant) -> Result<(), String> {
+    let ModelVariant::Custom(id) = &variant else {
+        return Err("Only custom models can be removed".to_string());
+    };
+    let model = custom_model(id).ok_or_else(|| format!("Custom model not found: {}", id))?;
+
+    unload_whisper_model(Some(variant.clone())).await?;
+
+    let mut models = CUSTOM_MODELS.lock().unwrap();
+    models.retain(|existing| existing.variant != variant);
+    save_registry(&app_handle, &models)?;
+
+    // For a link this removes the link, not the file it points to
+    let path = models_directory(&app_handle)?.join(&model.filename);
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(format!("Failed to delete model file: {}", e)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("voice-intelligence-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn corrupt_registry_is_moved_aside() {
+        let directory = test_directory("registry");
+        let path = directory.join(REGISTRY_FILE);
+        fs::write(&path, "[{\"variant\":").unwrap();
+
+        assert!(load_registry(&path).is_err());
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(directory.join("custom-models.json.bak")).unwrap(), "[{\"variant\":");
+
+        // A missing registry is simply empty
+        assert!(load_registry(&path).unwrap().is_empty());
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    fn registry_entry(id: &str) -> CustomModel {
+        CustomModel {
+            variant: ModelVariant::Custom(id.to_string()),
+            name: id.to_string(),
+            filename: custom_model_filename(id),
+            source_path: format!("/models/{}.bin", id),
+            linked: false,
+            imported_at: 0,
+            size: 5,
+            sha256: String::new(),
+            header: ModelHeader {
+                n_vocab: 51_865,
+                n_audio_ctx: 1500,
+                n_audio_state: 384,
+                n_audio_head: 6,
+                n_audio_layer: 4,
+                n_text_ctx: 448,
+                n_text_state: 384,
+                n_text_head: 6,
+                n_text_layer: 4,
+                n_mels: 80,
+                ftype: 1,
+            },
+            family: ModelFamily::Whisper,
+            model_size: Some(ModelSize::Tiny),
+            quantization: Quantization::F16,
+            languages: LanguageSupport::Multilingual,
+            memory_requirement: 300 * MB,
+        }
+    }
+
+    #[test]
+    fn startup_cleanup_keeps_files_when_registry_is_unusable() {
+        let directory = test_directory("cleanup");
+        let path = directory.join(REGISTRY_FILE);
+        fs::write(directory.join("custom-kept.bin"), b"model").unwrap();
+        fs::write(directory.join("custom-orphan.bin"), b"model").unwrap();
+        fs::write(directory.join("ggml-base.bin"), b"model").unwrap();
+
+        fs::write(&path, "not json").unwrap();
+        assert!(load_and_clean(&directory).is_err());
+        assert!(directory.join("custom-orphan.bin").exists());
+
+        // The backup of the corrupt registry still protects the files
+        let registry = vec![registry_entry("custom-kept"), registry_entry("custom-gone")];
+        fs::write(&path, serde_json::to_string(&registry).unwrap()).unwrap();
+        let (kept, dropped) = load_and_clean(&directory).unwrap();
+        assert_eq!(kept.len(), 1);
+        assert!(dropped);
+        assert!(directory.join("custom-orphan.bin").exists());
+
+        fs::remove_file(directory.join("custom-models.json.bak")).unwrap();
+        load_and_clean(&directory).unwrap();
+        assert!(directory.join("custom-kept.bin").exists());
+        assert!(!directory.join("custom-orphan.bin").exists());
+        assert!(directory.join("ggml-base.bin").exists());
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn concurrent_imports_get_distinct_ids() {
+        let directory = test_directory("reserve");
+        fs::write(directory.join("custom-whisper-nl.bin"), b"taken").unwrap();
+
+        let first = reserve_id("Whisper NL", &directory);
+        let second = reserve_id("Whisper NL", &directory);
+        assert_eq!(first.0, "custom-whisper-nl-2");
+        assert_eq!(second.0, "custom-whisper-nl-3");
+
+        drop(first);
+        assert_eq!(reserve_id("Whisper NL", &directory).0, "custom-whisper-nl-2");
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn copy_does_not_replace_existing_file() {
+        let directory = test_directory("copy");
+        let source = directory.join("source.bin");
+        let target = directory.join("custom-model.bin");
+        fs::write(&source, b"new").unwrap();
+        fs::write(&target, b"existing").unwrap();
+
+        assert!(copy_model_file(&source, &target).is_err());
+        assert_eq!(fs::read(&target).unwrap(), b"existing");
+
+        fs::remove_file(&target).unwrap();
+        copy_model_file(&source, &target).unwrap();
+        assert_eq!(fs::read(&target).unwrap(), b"new");
+        let _ = fs::remove_dir_all(&directory);
+    }
+}
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use whisper_rs::{FullParams, SamplingStrategy};
+
+const MAX_CANDIDATES: u32 = 16;
+const MAX_SEGMENT_LENGTH: u32 = 1_000;
+
+/// How whisper picks tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SearchStrategy {
+    Greedy { best_of: u32 },                   // Best of `best_of` samples when sampling with temperature
+    BeamSearch { beam_size: u32, patience: f32 }, // Patience is not implemented by whisper.cpp; -1.0 keeps its default
+}
+
+/// Named starting points for `DecodingConfig`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DecodingPreset {
+    Fast,     // Greedy without temperature fallback
+    Balanced, // Greedy with fallback, the default
+    Accurate, // Beam search with fallback
+}
+
+impl DecodingPreset {
+    pub const ALL: [DecodingPreset; 3] = [DecodingPreset::Fast, DecodingPreset::Balanced, DecodingPreset::Accurate];
+}
+
+/// Decoder settings passed through to whisper's `FullParams`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecodingConfig {
+    pub strategy: SearchStrategy,
+    pub temperature: f32,           // Initial sampling temperature, 0.0 is deterministic
+    pub temperature_increment: f32, // Retry step when a decode fails the thresholds below; 0.0 disables fallback
+    pub entropy_threshold: f32,     // Retry when the compression ratio entropy exceeds this
+    pub logprob_threshold: f32,     // Retry when the average log probability falls below this
+    pub no_speech_threshold: f32,   // Treat a segment as silence above this no-speech probability
+    pub max_segment_length: u32,    // Split segments at word boundaries beyond this many characters; 0 for no limit
+    pub suppress_blank: bool,       // Suppress blank outputs at the start of sampling
+    pub suppress_non_speech_tokens: bool, // Suppress tokens like "[Music]" and "(laughs)"
+    pub n_threads: Option<usize>,   // None uses the threads assigned to the job
+}
+
+impl Default for DecodingConfig {
+    fn default() -> Self {
+        DecodingConfig::preset(DecodingPreset::Balanced)
+    }
+}
+
+impl DecodingConfig {
+    pub fn preset(preset: DecodingPreset) -> Self {
+        // Thresholds follow whisper.cpp's defaults
+        let base = DecodingConfig {
+            strategy: SearchStrategy::Greedy { best_of: 1 },
+            temperature: 0.0,
+            temperature_increment: 0.2,
+            entropy_threshold: 2.4,
+            logprob_threshold: -1.0,
+            no_speech_threshold: 0.6,
+            max_segment_length: 0,
+            suppress_blank: true,
+            suppress_non_speech_tokens: false,
+            n_threads: None,
+        };
+
+        match preset {
+            DecodingPreset::Fast => DecodingConfig {
+                temperature_increment: 0.0,
+                ..base
+            },
+            DecodingPreset::Balanced => base,
+            DecodingPreset::Accurate => DecodingConfig {
+                strategy: SearchStrategy::BeamSearch { beam_size: 5, patience: -1.0 },
+                ..base
+            },
+        }
+    }
+
+    /// The preset's settings with the fields present in `overrides` replaced.
+    /// Missing fields keep the preset's values.
+    pub fn with_overrides(
+        preset: DecodingPreset,
+        overrides: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<Self, String> {
+        let mut config = match serde_json::to_value(DecodingConfig::preset(preset)) {
+            Ok(serde_json::Value::Object(config)) => config,
+            _ => return Err("Failed to serialize decoding preset".to_string()),
+        };
+        config.extend(overrides);
+
+        serde_json::from_value(serde_json::Value::Object(config))
+            .map_err(|e| format!("Invalid decoding settings: {}", e))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self.strategy {
+            SearchStrategy::Greedy { best_of } if !(1..=MAX_CANDIDATES).contains(&best_of) => {
+                return Err(format!("best_of must be between 1 and {}", MAX_CANDIDATES));
+            }
+            SearchStrategy::BeamSearch { beam_size, .. } if !(1..=MAX_CANDIDATES).contains(&beam_size) => {
+                return Err(format!("Beam size must be between 1 and {}", MAX_CANDIDATES));
+            }
+            SearchStrategy::BeamSearch { patience, .. } if patience.is_nan() || (patience <= 0.0 && patience != -1.0) => {
+                return Err("Beam search patience must be positive, or -1.0 for the default".to_string());
+            }
+            _ => {}
+        }
+        if !(0.0..=1.0).contains(&self.temperature) {
+            return Err("Temperature must be between 0.0 and 1.0".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.temperature_increment) {
+            return Err("Temperature increment must be between 0.0 and 1.0".to_string());
+        }
+        if self.entropy_threshold.is_nan() || self.entropy_threshold <= 0.0 {
+            return Err("Entropy threshold must be positive".to_string());
+        }
+        if self.logprob_threshold.is_nan() || self.logprob_threshold > 0.0 {
+            return Err("Log probability threshold must be at most 0.0".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.no_speech_threshold) {
+            return Err("No-speech threshold must be between 0.0 and 1.0".to_string());
+        }
+        if self.max_segment_length > MAX_SEGMENT_LENGTH {
+            return Err(format!("Maximum segment length must be at most {} characters", MAX_SEGMENT_LENGTH));
+        }
+        if self.n_threads == Some(0) {
+            return Err("Thread count must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Threads to decode with, never more than `available`
+    pub fn threads(&self, available: usize) -> usize {
+        self.n_threads.map_or(available, |n_threads| n_threads.min(available)).max(1)
+    }
+
+    /// Whisper parameters for this config. Language, task and output
+    /// settings are left to the caller.
+    pub fn full_params<'a, 'b>(&self, n_threads: usize) -> FullParams<'a, 'b> {
+        let strategy = match self.strategy {
+            SearchStrategy::Greedy { best_of } => SamplingStrategy::Greedy { best_of: best_of as i32 },
+            SearchStrategy::BeamSearch { beam_size, patience } => SamplingStrategy::BeamSearch {
+                beam_size: beam_size as i32,
+                patience,
+            },
+        };
+
+        let mut params = FullParams::new(strategy);
+        params.set_n_threads(n_threads as i32);
+        params.set_temperature(self.temperature);
+        params.set_temperature_inc(self.temperature_increment);
+        params.set_entropy_thold(self.entropy_threshold);
+        params.set_logprob_thold(self.logprob_threshold);
+        params.set_no_speech_thold(self.no_speech_threshold);
+        params.set_suppress_blank(self.suppress_blank);
+        params.set_suppress_nst(self.suppress_non_speech_tokens);
+        if self.max_segment_length > 0 {
+            params.set_max_len(self.max_segment_length as i32);
+            params.set_split_on_word(true);
+        }
+        params
+    }
+}
+
+/// A preset together with the settings it stands for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedDecodingConfig {
+    pub preset: DecodingPreset,
+    pub config: DecodingConfig,
+}
+
+/// All decoding presets, for the frontend to offer and customize
+#[tauri::command]
+pub async fn list_decoding_presets() -> Result<Vec<NamedDecodingConfig>, String> {
+    Ok(DecodingPreset::ALL
+        .iter()
+        .map(|&preset| NamedDecodingConfig {
+            preset,
+            config: DecodingConfig::preset(preset),
+        })
+        .collect())
+}
@@ -0,0 +1,336 @@
+use once_cell::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, Sender};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::ShortcutState;
+
+use crate::audio_archive::archive_settings;
+use crate::audio_capture::{begin_capture, end_capture, is_capturing, take_last_recording};
+use crate::history::save_dictation;
+use crate::model_pool::get_model;
+use crate::output_sink::{deliver_text, EnigoSynthesizer, OutputDelivery};
+use crate::settings_store::{load_settings, save_settings};
+use crate::transcription_jobs::{transcribe_queued, TranscriptionPriority};
+use crate::whisper::{ModelVariant, TranscriptionOptions, TranscriptionResult};
+
+// Shorter presses are treated as accidental taps in push-to-talk mode
+const DEFAULT_MIN_HOLD_MS: u64 = 300;
+const MAX_MIN_HOLD_MS: u64 = 5_000;
+
+const SETTINGS_FILE: &str = "dictation.json";
+
+/// How a recording hotkey behaves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyMode {
+    #[default]
+    Toggle,     // Press once to start, again to stop and transcribe
+    PushToTalk, // Record while held, transcribe on release
+}
+
+/// Model, decoding options and push-to-talk timing used for hotkey recordings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DictationSettings {
+    pub variant: Option<ModelVariant>, // None uses the most recently used loaded model
+    pub options: TranscriptionOptions,
+    pub min_hold_ms: u64, // Push-to-talk presses shorter than this are discarded
+}
+
+impl Default for DictationSettings {
+    fn default() -> Self {
+        DictationSettings {
+            variant: None,
+            options: TranscriptionOptions::default(),
+            min_hold_ms: DEFAULT_MIN_HOLD_MS,
+        }
+    }
+}
+
+impl DictationSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        self.options.validate()?;
+        if self.min_hold_ms > MAX_MIN_HOLD_MS {
+            return Err(format!("Minimum hold time must be at most {} ms", MAX_MIN_HOLD_MS));
+        }
+        Ok(())
+    }
+}
+
+/// Payload of the `dictation-state` event, sent when a dictation recording
+/// starts or stops
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictationState {
+    pub recording: bool,
+}
+
+/// Payload of the `dictation-result` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictationOutcome {
+    pub duration_ms: u64,
+    pub result: Option<TranscriptionResult>,
+    pub error: Option<String>,
+    pub output: Option<OutputDelivery>, // How the text reached the focused application
+    pub output_error: Option<String>,
+    pub recording_id: Option<String>, // History entry the transcript was saved to
+}
+
+lazy_static::lazy_static! {
+    static ref DICTATION_SETTINGS: Mutex<DictationSettings> = Mutex::new(DictationSettings::default());
+    // When the push-to-talk key went down, while it is held
+    static ref PUSH_TO_TALK: Mutex<Option<Instant>> = Mutex::new(None);
+    // Hotkey events are handled in order on one worker thread, so a release
+    // never overtakes the press that is still opening the input device
+    static ref HOTKEY_EVENTS: Mutex<Sender<(AppHandle, HotkeyEvent)>> = Mutex::new(spawn_hotkey_worker());
+    static ref LAST_TRANSCRIPT: Mutex<Option<String>> = Mutex::new(None);
+}
+
+enum HotkeyEvent {
+    Recording(HotkeyMode, ShortcutState),
+    Toggle, // Tray menu and `toggle_recording`, regardless of the hotkey mode
+    Cancel,
+}
+
+fn emit_dictation_state(app_handle: &AppHandle, recording: bool) {
+    let _ = app_handle.emit("dictation-state", DictationState { recording });
+}
+
+fn dictation_variant(settings: &DictationSettings) -> Result<ModelVariant, String> {
+    match &settings.variant {
+        Some(variant) => Ok(variant.clone()),
+        None => get_model(None).map(|context| context.variant().clone()),
+    }
+}
+
+/// Start recording for dictation from the selected input device
+pub fn start_dictation(app_handle: &AppHandle) -> Result<(), String> {
+    begin_capture(app_handle)?;
+    emit_dictation_state(app_handle, true);
+    Ok(())
+}
+
+/// Stop recording and transcribe it in the background with dictation priority.
+/// The outcome is reported through the `dictation-result` event.
+pub fn finish_dictation(app_handle: &AppHandle) -> Result<(), String> {
+    let captured = end_capture(app_handle)?;
+    emit_dictation_state(app_handle, false);
+    let audio_data = take_last_recording().unwrap_or_default();
+    let archive_audio = archive_settings().archive_dictations.then(|| audio_data.clone());
+    let settings = DICTATION_SETTINGS.lock().unwrap().clone();
+    let app = app_handle.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let variant = dictation_variant(&settings);
+        let model = variant.as_ref().ok().map(|variant| variant.to_filename());
+        let outcome = match variant {
+            Ok(variant) => {
+                transcribe_queued(
+                    audio_data,
+                    variant,
+                    settings.options,
+                    TranscriptionPriority::Dictation,
+                    app.clone(),
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+
+        let (result, error) = match outcome {
+            Ok(result) => {
+                *LAST_TRANSCRIPT.lock().unwrap() = Some(result.text.clone());
+                (Some(result), None)
+            }
+            Err(e) => (None, Some(e)),
+        };
+
+        // Hand the text to the output sink: clipboard, then paste or type if configured
+        let (output, output_error) = match result.as_ref().map(|result| result.text.clone()) {
+            Some(text) if !text.is_empty() => {
+                let output_app = app.clone();
+                let delivery = tauri::async_runtime::spawn_blocking(move || {
+                    deliver_text(&output_app, &text, None, &mut EnigoSynthesizer::default())
+                })
+                .await
+                .map_err(|e| format!("Output task failed: {}", e))
+                .and_then(|delivery| delivery);
+                match delivery {
+                    Ok(delivery) => (Some(delivery), None),
+                    Err(e) => (None, Some(e)),
+                }
+            }
+            _ => (None, None),
+        };
+
+        // A failed history write must not lose the transcript, which was already delivered
+        let recording_id = match result.clone().filter(|result| !result.text.is_empty()) {
+            Some(result) => {
+                let history_app = app.clone();
+                let saved = tauri::async_runtime::spawn_blocking(move || {
+                    save_dictation(
+                        &history_app,
+                        captured.duration_ms,
+                        &result,
+                        model.as_deref(),
+                        archive_audio.as_deref(),
+                    )
+                })
+                .await
+                .map_err(|e| format!("History task failed: {}", e))
+                .and_then(|saved| saved);
+                saved
+                    .map_err(|e| eprintln!("Failed to save dictation to history: {}", e))
+                    .ok()
+            }
+            None => None,
+        };
+
+        let _ = app.emit("dictation-result", DictationOutcome {
+            duration_ms: captured.duration_ms,
+            result,
+            error,
+            output,
+            output_error,
+            recording_id,
+        });
+    });
+
+    Ok(())
+}
+
+/// Stop recording and throw the audio away
+pub fn discard_dictation(app_handle: &AppHandle) -> Result<(), String> {
+    end_capture(app_handle)?;
+    emit_dictation_state(app_handle, false);
+    take_last_recording();
+    Ok(())
+}
+
+/// Start dictation, or finish it when a recording is already running
+pub fn toggle_dictation(app_handle: &AppHandle) -> Result<(), String> {
+    if is_capturing() {
+        finish_dictation(app_handle)
+    } else {
+        start_dictation(app_handle)
+    }
+}
+
+fn push_to_talk(app_handle: &AppHandle, state: ShortcutState, min_hold: Duration) -> Result<(), String> {
+    let mut pressed_at = PUSH_TO_TALK.lock().unwrap();
+
+    match state {
+        ShortcutState::Pressed => {
+            // Key repeat sends further presses, and a toggle recording may already be running
+            if pressed_at.is_some() || is_capturing() {
+                return Ok(());
+            }
+            start_dictation(app_handle)?;
+            *pressed_at = Some(Instant::now());
+            Ok(())
+        }
+        ShortcutState::Released => match pressed_at.take() {
+            Some(pressed) if pressed.elapsed() < min_hold => discard_dictation(app_handle),
+            Some(_) => finish_dictation(app_handle),
+            None => Ok(()),
+        },
+    }
+}
+
+fn cancel_hotkey_recording(app_handle: &AppHandle) -> Result<(), String> {
+    // A held push-to-talk key must not finish the cancelled recording on release
+    PUSH_TO_TALK.lock().unwrap().take();
+    if is_capturing() {
+        discard_dictation(app_handle)
+    } else {
+        Ok(())
+    }
+}
+
+fn spawn_hotkey_worker() -> Sender<(AppHandle, HotkeyEvent)> {
+    let (sender, receiver) = mpsc::channel::<(AppHandle, HotkeyEvent)>();
+
+    std::thread::spawn(move || {
+        for (app_handle, event) in receiver {
+            let min_hold = Duration::from_millis(DICTATION_SETTINGS.lock().unwrap().min_hold_ms);
+
+            let result = match event {
+                HotkeyEvent::Recording(HotkeyMode::Toggle, ShortcutState::Pressed) => toggle_dictation(&app_handle),
+                // Toggle mode only reacts to presses
+                HotkeyEvent::Recording(HotkeyMode::Toggle, ShortcutState::Released) => Ok(()),
+                HotkeyEvent::Recording(HotkeyMode::PushToTalk, state) => push_to_talk(&app_handle, state, min_hold),
+                HotkeyEvent::Toggle => toggle_dictation(&app_handle),
+                HotkeyEvent::Cancel => cancel_hotkey_recording(&app_handle),
+            };
+            if let Err(e) = result {
+                eprintln!("Dictation hotkey failed: {}", e);
+            }
+        }
+    });
+
+    sender
+}
+
+/// Handle a press or release of a recording hotkey. Capture is started off
+/// the event loop thread.
+pub fn handle_dictation_hotkey(app_handle: &AppHandle, mode: HotkeyMode, state: ShortcutState) {
+    let event = HotkeyEvent::Recording(mode, state);
+    let _ = HOTKEY_EVENTS.lock().unwrap().send((app_handle.clone(), event));
+}
+
+/// Start or finish dictation on the hotkey worker, so callers on the event
+/// loop thread do not wait for the input device to open
+pub fn request_dictation_toggle(app_handle: &AppHandle) {
+    let _ = HOTKEY_EVENTS.lock().unwrap().send((app_handle.clone(), HotkeyEvent::Toggle));
+}
+
+/// Discard a running hotkey recording without transcribing it
+pub fn cancel_dictation_hotkey(app_handle: &AppHandle) {
+    let _ = HOTKEY_EVENTS.lock().unwrap().send((app_handle.clone(), HotkeyEvent::Cancel));
+}
+
+/// Load the stored dictation settings. Call during setup, before any hotkey
+/// can start a recording.
+pub fn init_dictation_settings(app_handle: &AppHandle) {
+    match load_settings(app_handle, SETTINGS_FILE, DictationSettings::validate) {
+        Ok(Some(settings)) => *DICTATION_SETTINGS.lock().unwrap() = settings,
+        Ok(None) => {}
+        Err(e) => eprintln!("Ignoring stored dictation settings: {}", e),
+    }
+}
+
+/// Text of the most recent successful dictation
+pub fn last_transcript() -> Option<String> {
+    LAST_TRANSCRIPT.lock().unwrap().clone()
+}
+
+#[tauri::command]
+pub async fn get_dictation_settings() -> Result<DictationSettings, String> {
+    Ok(DICTATION_SETTINGS.lock().unwrap().clone())
+}
+
+#[tauri::command]
+pub async fn set_dictation_settings(settings: DictationSettings, app_handle: AppHandle) -> Result<(), String> {
+    settings.validate()?;
+    save_settings(&app_handle, SETTINGS_FILE, &settings)?;
+    *DICTATION_SETTINGS.lock().unwrap() = settings;
+    Ok(())
+}
+
+/// Transcribe the last recording made with `start_capture`/`stop_capture`
+#[tauri::command]
+pub async fn transcribe_last_capture(
+    variant: ModelVariant,
+    options: Option<TranscriptionOptions>,
+    app_handle: AppHandle,
+) -> Result<TranscriptionResult, String> {
+    let audio_data = take_last_recording().ok_or("No captured audio to transcribe")?;
+    transcribe_queued(
+        audio_data,
+        variant,
+        options.unwrap_or_default(),
+        TranscriptionPriority::Dictation,
+        app_handle,
+    )
+    .await
+}
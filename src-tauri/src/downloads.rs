@@ -0,0 +1,640 @@
+use futures_util::StreamExt;
+use once_cell::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::watch;
+
+use crate::file_utils::calculate_file_checksum;
+
+// Retries of transient download failures, with exponential backoff in between
+const DOWNLOAD_MAX_ATTEMPTS: u32 = 5;
+const DOWNLOAD_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const DOWNLOAD_MAX_BACKOFF: Duration = Duration::from_secs(30);
+// A connection that delivers nothing for this long is treated as dropped
+const DOWNLOAD_STALL_TIMEOUT: Duration = Duration::from_secs(60);
+// Throughput is measured over windows of this length and smoothed
+const RATE_WINDOW: Duration = Duration::from_millis(500);
+const RATE_SMOOTHING: f64 = 0.3;
+
+const DOWNLOAD_CANCELLED: &str = "Download cancelled";
+
+/// Payload of the `download-progress` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadProgress {
+    pub download_id: String,
+    pub bytes_downloaded: u64,
+    pub total_bytes: u64,
+    pub percentage: f64,
+    pub bytes_per_second: f64, // Smoothed throughput, 0 while not transferring
+    pub status: String, // starting, downloading, resumed, retrying, paused, validating, completed, cancelled, failed
+}
+
+/// An active or paused download, as returned by `list_downloads`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadInfo {
+    pub download_id: String,
+    pub url: String, // Mirror currently in use
+    pub target_path: String,
+    pub progress: DownloadProgress, // Latest progress reported for the download
+}
+
+/// What the user asked a download to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DownloadSignal {
+    Run,
+    Pause,
+    Cancel,
+}
+
+struct DownloadEntry {
+    info: DownloadInfo,
+    signal: watch::Sender<DownloadSignal>,
+}
+
+lazy_static::lazy_static! {
+    // Downloads are removed once they complete, fail or are cancelled
+    static ref DOWNLOADS: Mutex<HashMap<String, DownloadEntry>> = Mutex::new(HashMap::new());
+}
+
+static NEXT_DOWNLOAD_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Validators of a partial download, stored next to the `.tmp` file so an
+/// interrupted download can be resumed only if the remote file is unchanged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PartialDownload {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl PartialDownload {
+    /// Value for `If-Range`; weak ETags are not allowed there
+    fn if_range(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+}
+
+enum DownloadError {
+    Transient(String), // Worth retrying; the partial file is kept
+    Fatal(String),
+    Interrupted,       // Paused or cancelled by the user
+}
+
+/// Exponentially smoothed transfer rate
+struct RateMeter {
+    window_start: Instant,
+    window_bytes: u64,
+    bytes_per_second: f64,
+}
+
+impl RateMeter {
+    fn new() -> Self {
+        RateMeter {
+            window_start: Instant::now(),
+            window_bytes: 0,
+            bytes_per_second: 0.0,
+        }
+    }
+
+    fn add(&mut self, bytes: u64) {
+        self.window_bytes += bytes;
+        let elapsed = self.window_start.elapsed();
+        if elapsed < RATE_WINDOW {
+            return;
+        }
+
+        let current = self.window_bytes as f64 / elapsed.as_secs_f64();
+        self.bytes_per_second = if self.bytes_per_second == 0.0 {
+            current
+        } else {
+            self.bytes_per_second + RATE_SMOOTHING * (current - self.bytes_per_second)
+        };
+        self.window_start = Instant::now();
+        self.window_bytes = 0;
+    }
+}
+
+/// State of one running download, shared by its attempts
+struct Transfer {
+    app_handle: Option<AppHandle>, // None when nothing listens for progress
+    download_id: String,
+    urls: Vec<String>, // Mirrors, tried in order
+    mirror: usize,
+    temp_path: String,
+    meta_path: String,
+    expected_size: u64,
+    signal: watch::Receiver<DownloadSignal>,
+    rate: RateMeter,
+}
+
+impl Transfer {
+    fn report(&self, downloaded: u64, status: &str) {
+        let transferring = matches!(status, "downloading" | "resumed");
+        let progress = DownloadProgress {
+            download_id: self.download_id.clone(),
+            bytes_downloaded: downloaded,
+            total_bytes: self.expected_size,
+            percentage: if self.expected_size > 0 {
+                (downloaded as f64 / self.expected_size as f64 * 100.0).min(100.0)
+            } else {
+                0.0
+            },
+            bytes_per_second: if transferring { self.rate.bytes_per_second } else { 0.0 },
+            status: status.to_string(),
+        };
+
+        if let Some(entry) = DOWNLOADS.lock().unwrap().get_mut(&self.download_id) {
+            entry.info.progress = progress.clone();
+        }
+        if let Some(app_handle) = &self.app_handle {
+            let _ = app_handle.emit("download-progress", progress);
+        }
+    }
+
+    fn url(&self) -> &str {
+        &self.urls[self.mirror]
+    }
+
+    /// Move on to the next mirror, if there is one
+    fn next_mirror(&mut self) -> bool {
+        if self.mirror + 1 >= self.urls.len() {
+            return false;
+        }
+        self.mirror += 1;
+        if let Some(entry) = DOWNLOADS.lock().unwrap().get_mut(&self.download_id) {
+            entry.info.url = self.url().to_string();
+        }
+        true
+    }
+
+    fn current_signal(&self) -> DownloadSignal {
+        *self.signal.borrow()
+    }
+
+    /// Resolves once the user pauses or cancels, or the entry is gone
+    async fn interrupted(&mut self) {
+        let _ = self.signal.wait_for(|signal| *signal != DownloadSignal::Run).await;
+    }
+
+    fn bytes_on_disk(&self) -> u64 {
+        fs::metadata(&self.temp_path).map(|metadata| metadata.len()).unwrap_or(0)
+    }
+
+    /// One download attempt into the temp file, continuing an earlier partial
+    /// download when its validators still match. Returns the bytes on disk.
+    async fn attempt(&mut self, client: &reqwest::Client) -> Result<u64, DownloadError> {
+        use reqwest::header::{ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+        use reqwest::StatusCode;
+
+        let url = self.url().to_string();
+        let partial = read_partial_download(&self.meta_path, &url);
+        let existing = match (&partial, fs::metadata(&self.temp_path)) {
+            (Some(_), Ok(metadata)) if metadata.len() > 0 => metadata.len(),
+            _ => 0,
+        };
+
+        let mut request = client.get(&url);
+        if let Some(validator) = partial.as_ref().and_then(|partial| partial.if_range()).filter(|_| existing > 0) {
+            // The server answers 200 with the whole file if it changed since
+            request = request
+                .header(RANGE, format!("bytes={}-", existing))
+                .header(IF_RANGE, validator);
+        }
+
+        let response = tokio::select! {
+            response = request.send() => response.map_err(|e| {
+                DownloadError::Transient(format!("Download request failed: {}. Please check your internet connection.", e))
+            })?,
+            _ = self.interrupted() => return Err(DownloadError::Interrupted),
+        };
+
+        let status = response.status();
+        let resumed = existing > 0 && status == StatusCode::PARTIAL_CONTENT;
+
+        if existing > 0 && status == StatusCode::RANGE_NOT_SATISFIABLE {
+            // Nothing left to fetch when the partial file is already complete
+            if self.expected_size > 0 && existing == self.expected_size {
+                return Ok(existing);
+            }
+            discard_partial_download(&self.temp_path, &self.meta_path);
+            return Err(DownloadError::Transient("Partial download no longer matches the remote file".to_string()));
+        }
+
+        if !status.is_success() {
+            let message = format!(
+                "Download failed with HTTP status: {}. The model file may not be available.",
+                status
+            );
+            let transient = status.is_server_error()
+                || status == StatusCode::TOO_MANY_REQUESTS
+                || status == StatusCode::REQUEST_TIMEOUT;
+            return Err(if transient { DownloadError::Transient(message) } else { DownloadError::Fatal(message) });
+        }
+
+        if resumed && content_range_start(&response) != Some(existing) {
+            discard_partial_download(&self.temp_path, &self.meta_path);
+            return Err(DownloadError::Transient("Server resumed the download at the wrong offset".to_string()));
+        }
+
+        let mut file = if resumed {
+            fs::OpenOptions::new().append(true).open(&self.temp_path)
+        } else {
+            // A fresh download: remember how to validate a later resume
+            let partial = PartialDownload {
+                url,
+                etag: header_string(&response, ETAG),
+                last_modified: header_string(&response, LAST_MODIFIED),
+            };
+            let _ = fs::write(&self.meta_path, serde_json::to_string(&partial).unwrap_or_default());
+            fs::File::create(&self.temp_path)
+        }
+        .map_err(|e| DownloadError::Fatal(format!("Failed to create file: {}. Check disk permissions.", e)))?;
+
+        let mut downloaded = if resumed { existing } else { 0 };
+        self.rate = RateMeter::new();
+        self.report(downloaded, if resumed { "resumed" } else { "downloading" });
+
+        let mut stream = response.bytes_stream();
+        loop {
+            // A stalled connection counts as interrupted, so it can be resumed
+            let next = tokio::select! {
+                next = tokio::time::timeout(DOWNLOAD_STALL_TIMEOUT, stream.next()) => next,
+                _ = self.interrupted() => return Err(DownloadError::Interrupted),
+            };
+            let chunk = match next {
+                Ok(Some(Ok(chunk))) => chunk,
+                Ok(Some(Err(e))) => {
+                    return Err(DownloadError::Transient(format!("Failed to read data chunk: {}. Download interrupted.", e)));
+                }
+                Ok(None) => break,
+                Err(_) => return Err(DownloadError::Transient("Download stalled".to_string())),
+            };
+
+            file.write_all(&chunk).map_err(|e| {
+                DownloadError::Fatal(format!("Failed to write to disk: {}. Check available disk space.", e))
+            })?;
+
+            downloaded += chunk.len() as u64;
+            self.rate.add(chunk.len() as u64);
+            self.report(downloaded, "downloading");
+        }
+
+        // Ensure all data is written to disk
+        file.sync_all()
+            .map_err(|e| DownloadError::Fatal(format!("Failed to write to disk: {}", e)))?;
+
+        Ok(downloaded)
+    }
+
+    /// Run attempts until the file is complete, honouring pause and cancel.
+    /// Transient failures are retried with exponential backoff.
+    async fn fetch(&mut self) -> Result<u64, String> {
+        // No overall timeout: large models take longer than any fixed limit,
+        // stalls are detected per chunk instead
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        let mut backoff = DOWNLOAD_INITIAL_BACKOFF;
+        let mut attempt = 1;
+
+        loop {
+            match self.current_signal() {
+                DownloadSignal::Cancel => return Err(DOWNLOAD_CANCELLED.to_string()),
+                DownloadSignal::Pause => {
+                    // The partial file is kept; resuming continues it with a range request
+                    self.report(self.bytes_on_disk(), "paused");
+                    if self.signal.wait_for(|signal| *signal != DownloadSignal::Pause).await.is_err() {
+                        return Err(DOWNLOAD_CANCELLED.to_string());
+                    }
+                    backoff = DOWNLOAD_INITIAL_BACKOFF;
+                    attempt = 1;
+                    continue;
+                }
+                DownloadSignal::Run => {}
+            }
+
+            match self.attempt(&client).await {
+                Ok(downloaded) => return Ok(downloaded),
+                Err(DownloadError::Interrupted) => continue,
+                Err(DownloadError::Transient(e)) if attempt < DOWNLOAD_MAX_ATTEMPTS => {
+                    eprintln!("Download attempt {} failed, retrying in {:?}: {}", attempt, backoff, e);
+                    self.report(self.bytes_on_disk(), "retrying");
+                    tokio::select! {
+                        _ = tokio::time::sleep(backoff) => {}
+                        _ = self.interrupted() => {}
+                    }
+                    backoff = (backoff * 2).min(DOWNLOAD_MAX_BACKOFF);
+                    attempt += 1;
+                }
+                Err(DownloadError::Transient(e)) | Err(DownloadError::Fatal(e)) => {
+                    if !self.next_mirror() {
+                        // The partial file stays on disk so a later download can resume it
+                        return Err(e);
+                    }
+                    eprintln!("Download from mirror failed, trying {}: {}", self.url(), e);
+                    self.report(self.bytes_on_disk(), "retrying");
+                    backoff = DOWNLOAD_INITIAL_BACKOFF;
+                    attempt = 1;
+                }
+            }
+        }
+    }
+}
+
+fn read_partial_download(meta_path: &str, url: &str) -> Option<PartialDownload> {
+    let partial: PartialDownload = serde_json::from_str(&fs::read_to_string(meta_path).ok()?).ok()?;
+    (partial.url == url && partial.if_range().is_some()).then_some(partial)
+}
+
+fn discard_partial_download(temp_path: &str, meta_path: &str) {
+    let _ = fs::remove_file(temp_path);
+    let _ = fs::remove_file(meta_path);
+}
+
+/// Start offset of a `Content-Range: bytes <start>-<end>/<total>` header
+fn content_range_start(response: &reqwest::Response) -> Option<u64> {
+    let value = response.headers().get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+    value.strip_prefix("bytes ")?.split('-').next()?.trim().parse().ok()
+}
+
+fn header_string(response: &reqwest::Response, name: reqwest::header::HeaderName) -> Option<String> {
+    response.headers().get(name)?.to_str().ok().map(|value| value.to_string())
+}
+
+/// Register a download and return the transfer that performs it
+fn register_download(
+    app_handle: &AppHandle,
+    urls: Vec<String>,
+    target_path: String,
+    expected_size: u64,
+) -> Result<Transfer, String> {
+    if urls.is_empty() {
+        return Err("No download URL given".to_string());
+    }
+
+    let mut downloads = DOWNLOADS.lock().unwrap();
+    if downloads.values().any(|entry| entry.info.target_path == target_path) {
+        return Err(format!("{} is already being downloaded", target_path));
+    }
+
+    let download_id = format!("download-{}", NEXT_DOWNLOAD_ID.fetch_add(1, Ordering::SeqCst));
+    let (sender, receiver) = watch::channel(DownloadSignal::Run);
+    let info = DownloadInfo {
+        download_id: download_id.clone(),
+        url: urls[0].clone(),
+        target_path: target_path.clone(),
+        progress: DownloadProgress {
+            download_id: download_id.clone(),
+            bytes_downloaded: 0,
+            total_bytes: expected_size,
+            percentage: 0.0,
+            bytes_per_second: 0.0,
+            status: "starting".to_string(),
+        },
+    };
+    downloads.insert(download_id.clone(), DownloadEntry { info, signal: sender });
+
+    Ok(Transfer {
+        app_handle: Some(app_handle.clone()),
+        download_id,
+        urls,
+        mirror: 0,
+        temp_path: format!("{}.tmp", target_path),
+        meta_path: format!("{}.tmp.json", target_path),
+        expected_size,
+        signal: receiver,
+        rate: RateMeter::new(),
+    })
+}
+
+/// Download to `target_path` through `<target_path>.tmp`, verify the
+/// checksum and move the file into place. An interrupted download continues
+/// from the partial file, also in a later call, when the server supports
+/// range requests and the file has not changed.
+async fn run_download(mut transfer: Transfer, target_path: String, checksum: String) -> Result<(), String> {
+    let outcome = download_and_verify(&mut transfer, &target_path, &checksum).await;
+
+    match &outcome {
+        Ok(()) => {}
+        Err(e) if e == DOWNLOAD_CANCELLED => {
+            discard_partial_download(&transfer.temp_path, &transfer.meta_path);
+            transfer.report(0, "cancelled");
+        }
+        Err(_) => transfer.report(transfer.bytes_on_disk(), "failed"),
+    }
+    DOWNLOADS.lock().unwrap().remove(&transfer.download_id);
+
+    outcome
+}
+
+async fn download_and_verify(transfer: &mut Transfer, target_path: &str, checksum: &str) -> Result<(), String> {
+    // Ensure parent directory exists
+    if let Some(parent) = Path::new(target_path).parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    transfer.report(0, "starting");
+    let downloaded = transfer.fetch().await?;
+
+    transfer.report(downloaded, "validating");
+
+    // Validate checksum
+    let actual_checksum = calculate_file_checksum(transfer.temp_path.clone()).await.map_err(|e| {
+        discard_partial_download(&transfer.temp_path, &transfer.meta_path);
+        format!("Failed to calculate checksum: {}", e)
+    })?;
+
+    if actual_checksum.to_lowercase() != checksum.to_lowercase() {
+        // Delete the corrupted file so the next attempt starts from scratch
+        discard_partial_download(&transfer.temp_path, &transfer.meta_path);
+        return Err(format!(
+            "Checksum validation failed. The downloaded file is corrupted. Expected: {}, Got: {}. Please try downloading again.",
+            checksum, actual_checksum
+        ));
+    }
+
+    // Move temp file to final location
+    fs::rename(&transfer.temp_path, target_path).map_err(|e| {
+        discard_partial_download(&transfer.temp_path, &transfer.meta_path);
+        format!("Failed to finalize download: {}", e)
+    })?;
+    let _ = fs::remove_file(&transfer.meta_path);
+
+    transfer.report(downloaded, "completed");
+    Ok(())
+}
+
+fn send_signal(download_id: &str, signal: DownloadSignal) -> Result<(), String> {
+    let downloads = DOWNLOADS.lock().unwrap();
+    let entry = downloads
+        .get(download_id)
+        .ok_or_else(|| format!("Download not found: {}", download_id))?;
+    entry.signal.send_replace(signal);
+    Ok(())
+}
+
+/// Start a download in the background and return its ID right away. The
+/// URLs are mirrors of the same file; the next one is used when a mirror fails.
+pub(crate) fn spawn_download(
+    app_handle: &AppHandle,
+    urls: Vec<String>,
+    target_path: String,
+    expected_size: u64,
+    checksum: String,
+) -> Result<String, String> {
+    let transfer = register_download(app_handle, urls, target_path.clone(), expected_size)?;
+    let download_id = transfer.download_id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        match run_download(transfer, target_path, checksum).await {
+            Err(e) if e != DOWNLOAD_CANCELLED => eprintln!("Download failed: {}", e),
+            _ => {}
+        }
+    });
+
+    Ok(download_id)
+}
+
+/// Stop transferring but keep the partial file for `resume_download`
+#[tauri::command]
+pub async fn pause_download(download_id: String) -> Result<(), String> {
+    send_signal(&download_id, DownloadSignal::Pause)
+}
+
+#[tauri::command]
+pub async fn resume_download(download_id: String) -> Result<(), String> {
+    send_signal(&download_id, DownloadSignal::Run)
+}
+
+/// Stop the download and delete its partial file
+#[tauri::command]
+pub async fn cancel_download(download_id: String) -> Result<(), String> {
+    send_signal(&download_id, DownloadSignal::Cancel)
+}
+
+/// Downloads that are running or paused
+#[tauri::command]
+pub async fn list_downloads() -> Result<Vec<DownloadInfo>, String> {
+    let downloads = DOWNLOADS.lock().unwrap();
+    let mut infos: Vec<DownloadInfo> = downloads.values().map(|entry| entry.info.clone()).collect();
+    infos.sort_by_key(|info| info.download_id.trim_start_matches("download-").parse::<u64>().unwrap_or(0));
+    Ok(infos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    const ETAG: &str = "\"model-v1\"";
+
+    /// Request line and headers, with header names lowercased
+    fn read_request(reader: &mut BufReader<std::net::TcpStream>) -> Vec<String> {
+        let mut lines = Vec::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end().to_string();
+            if line.is_empty() {
+                return lines;
+            }
+            lines.push(match line.split_once(':') {
+                Some((name, value)) => format!("{}:{}", name.to_lowercase(), value),
+                None => line,
+            });
+        }
+    }
+
+    /// Serves `body` twice: the first response drops the connection halfway,
+    /// the second answers a range request. Returns the address and the headers seen.
+    fn serve_interrupted(body: Vec<u8>) -> (String, std::thread::JoinHandle<Vec<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}/model.bin", listener.local_addr().unwrap());
+
+        let server = std::thread::spawn(move || {
+            let half = body.len() / 2;
+            let mut requests = Vec::new();
+            for (index, stream) in listener.incoming().take(2).enumerate() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                requests.push(read_request(&mut reader));
+
+                if index == 0 {
+                    let head = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nETag: {}\r\nAccept-Ranges: bytes\r\n\r\n",
+                        body.len(),
+                        ETAG
+                    );
+                    stream.write_all(head.as_bytes()).unwrap();
+                    stream.write_all(&body[..half]).unwrap();
+                    // Dropping the stream ends the body early
+                } else {
+                    let head = format!(
+                        "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nETag: {}\r\n\r\n",
+                        body.len() - half,
+                        half,
+                        body.len() - 1,
+                        body.len(),
+                        ETAG
+                    );
+                    stream.write_all(head.as_bytes()).unwrap();
+                    stream.write_all(&body[half..]).unwrap();
+                }
+                stream.flush().unwrap();
+            }
+            requests
+        });
+
+        (address, server)
+    }
+
+    #[tokio::test]
+    async fn resumes_dropped_download_with_range_request() {
+        let body: Vec<u8> = (0..64 * 1024).map(|n| (n % 251) as u8).collect();
+        let (url, server) = serve_interrupted(body.clone());
+
+        let target = std::env::temp_dir().join(format!("voice-intelligence-download-{}.bin", std::process::id()));
+        let temp_path = format!("{}.tmp", target.display());
+        let meta_path = format!("{}.tmp.json", target.display());
+        discard_partial_download(&temp_path, &meta_path);
+
+        let (_sender, signal) = watch::channel(DownloadSignal::Run);
+        let mut transfer = Transfer {
+            app_handle: None,
+            download_id: "download-test".to_string(),
+            urls: vec![url],
+            mirror: 0,
+            temp_path: temp_path.clone(),
+            meta_path: meta_path.clone(),
+            expected_size: body.len() as u64,
+            signal,
+            rate: RateMeter::new(),
+        };
+
+        let downloaded = transfer.fetch().await.unwrap();
+        let contents = fs::read(&temp_path).unwrap();
+        discard_partial_download(&temp_path, &meta_path);
+
+        assert_eq!(downloaded, body.len() as u64);
+        assert_eq!(contents, body);
+
+        let requests = server.join().unwrap();
+        assert!(!requests[0].iter().any(|line| line.starts_with("range:")));
+        let half = body.len() / 2;
+        assert!(requests[1].contains(&format!("range: bytes={}-", half)));
+        assert!(requests[1].contains(&format!("if-range: {}", ETAG)));
+    }
+}
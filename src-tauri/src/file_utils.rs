@@ -1,17 +1,8 @@
-use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::fs;
-use std::io::{Read, Write};
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use tauri::AppHandle;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DownloadProgress {
-    pub bytes_downloaded: u64,
-    pub total_bytes: u64,
-    pub percentage: f64,
-    pub status: String,
-}
+use tauri::{AppHandle, Manager};
 
 #[tauri::command]
 pub async fn file_exists(path: String) -> Result<bool, String> {
@@ -23,9 +14,10 @@ pub async fn delete_file(path: String) -> Result<(), String> {
     fs::remove_file(&path).map_err(|e| format!("Failed to delete file: {}", e))
 }
 
-#[tauri::command]
-pub async fn calculate_file_checksum(path: String) -> Result<String, String> {
-    let mut file = fs::File::open(&path)
+/// SHA-256 of a file as lowercase hex. Reads the whole file, so call it off
+/// the async runtime.
+pub(crate) fn file_checksum(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path)
         .map_err(|e| format!("Failed to open file: {}", e))?;
     
     let mut hasher = Sha256::new();
@@ -45,6 +37,14 @@ pub async fn calculate_file_checksum(path: String) -> Result<String, String> {
     Ok(hex::encode(hasher.finalize()))
 }
 
+/// Hash a file on a blocking worker; multi-gigabyte models take seconds
+#[tauri::command]
+pub async fn calculate_file_checksum(path: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || file_checksum(Path::new(&path)))
+        .await
+        .map_err(|e| format!("Checksum task failed: {}", e))?
+}
+
 #[tauri::command]
 pub async fn get_available_disk_space(path: String) -> Result<u64, String> {
     // Use platform-specific methods to get disk space
@@ -69,6 +69,16 @@ pub async fn get_available_disk_space(path: String) -> Result<u64, String> {
 
 #[tauri::command]
 pub async fn get_models_directory(app_handle: AppHandle) -> Result<String, String> {
+    let models_dir = models_directory(&app_handle)?;
+    
+    models_dir
+        .to_str()
+        .ok_or("Invalid path".to_string())
+        .map(|s| s.to_string())
+}
+
+/// Resolve (and create if needed) the directory holding downloaded models
+pub fn models_directory(app_handle: &AppHandle) -> Result<PathBuf, String> {
     let app_data_dir = app_handle
         .path()
         .app_data_dir()
@@ -82,146 +92,32 @@ pub async fn get_models_directory(app_handle: AppHandle) -> Result<String, Strin
             .map_err(|e| format!("Failed to create models directory: {}", e))?;
     }
     
-    models_dir
+    Ok(models_dir)
+}
+
+#[tauri::command]
+pub async fn get_recordings_directory(app_handle: AppHandle) -> Result<String, String> {
+    let recordings_dir = recordings_directory(&app_handle)?;
+    
+    recordings_dir
         .to_str()
         .ok_or("Invalid path".to_string())
         .map(|s| s.to_string())
 }
 
-#[tauri::command]
-pub async fn download_model(
-    app_handle: AppHandle,
-    url: String,
-    target_path: String,
-    expected_size: u64,
-    checksum: String,
-) -> Result<(), String> {
-    // Ensure parent directory exists
-    if let Some(parent) = Path::new(&target_path).parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
-    }
-    
-    // Emit starting status
-    let _ = app_handle.emit("download-progress", DownloadProgress {
-        bytes_downloaded: 0,
-        total_bytes: expected_size,
-        percentage: 0.0,
-        status: "starting".to_string(),
-    });
-    
-    // Download the file with timeout and retry logic
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(300)) // 5 minute timeout
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-    
-    let response = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| {
-            // Cleanup on connection failure
-            let _ = fs::remove_file(&target_path);
-            format!("Download request failed: {}. Please check your internet connection.", e)
-        })?;
-    
-    if !response.status().is_success() {
-        return Err(format!(
-            "Download failed with HTTP status: {}. The model file may not be available.",
-            response.status()
-        ));
-    }
-    
-    // Create temporary file for download
-    let temp_path = format!("{}.tmp", target_path);
-    let mut file = fs::File::create(&temp_path)
-        .map_err(|e| format!("Failed to create file: {}. Check disk permissions.", e))?;
-    
-    let mut downloaded: u64 = 0;
-    let mut stream = response.bytes_stream();
-    
-    use futures_util::StreamExt;
-    
-    // Emit downloading status
-    let _ = app_handle.emit("download-progress", DownloadProgress {
-        bytes_downloaded: 0,
-        total_bytes: expected_size,
-        percentage: 0.0,
-        status: "downloading".to_string(),
-    });
-    
-    while let Some(chunk_result) = stream.next().await {
-        let chunk = chunk_result.map_err(|e| {
-            // Cleanup on stream error
-            let _ = fs::remove_file(&temp_path);
-            format!("Failed to read data chunk: {}. Download interrupted.", e)
-        })?;
-        
-        file.write_all(&chunk).map_err(|e| {
-            // Cleanup on write error
-            let _ = fs::remove_file(&temp_path);
-            format!("Failed to write to disk: {}. Check available disk space.", e)
-        })?;
-        
-        downloaded += chunk.len() as u64;
-        
-        // Emit progress event every chunk
-        let progress = DownloadProgress {
-            bytes_downloaded: downloaded,
-            total_bytes: expected_size,
-            percentage: if expected_size > 0 {
-                (downloaded as f64 / expected_size as f64) * 100.0
-            } else {
-                0.0
-            },
-            status: "downloading".to_string(),
-        };
-        
-        let _ = app_handle.emit("download-progress", progress);
-    }
-    
-    // Ensure all data is written to disk
-    drop(file);
-    
-    // Emit validating status
-    let _ = app_handle.emit("download-progress", DownloadProgress {
-        bytes_downloaded: downloaded,
-        total_bytes: expected_size,
-        percentage: 100.0,
-        status: "validating".to_string(),
-    });
+/// Resolve (and create if needed) the directory holding archived recording audio
+pub fn recordings_directory(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
     
-    // Validate checksum
-    let actual_checksum = calculate_file_checksum(temp_path.clone()).await.map_err(|e| {
-        // Cleanup on checksum calculation error
-        let _ = fs::remove_file(&temp_path);
-        format!("Failed to calculate checksum: {}", e)
-    })?;
+    let recordings_dir = app_data_dir.join("recordings");
     
-    if actual_checksum.to_lowercase() != checksum.to_lowercase() {
-        // Delete the corrupted file
-        let _ = fs::remove_file(&temp_path);
-        return Err(format!(
-            "Checksum validation failed. The downloaded file is corrupted. Expected: {}, Got: {}. Please try downloading again.",
-            checksum, actual_checksum
-        ));
+    if !recordings_dir.exists() {
+        fs::create_dir_all(&recordings_dir)
+            .map_err(|e| format!("Failed to create recordings directory: {}", e))?;
     }
     
-    // Move temp file to final location
-    fs::rename(&temp_path, &target_path).map_err(|e| {
-        // Cleanup on rename error
-        let _ = fs::remove_file(&temp_path);
-        format!("Failed to finalize download: {}", e)
-    })?;
-    
-    // Emit completed status
-    let _ = app_handle.emit("download-progress", DownloadProgress {
-        bytes_downloaded: downloaded,
-        total_bytes: expected_size,
-        percentage: 100.0,
-        status: "completed".to_string(),
-    });
-    
-    Ok(())
+    Ok(recordings_dir)
 }
@@ -0,0 +1,910 @@
+use once_cell::sync::Mutex;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::audio_archive::{remove_archived_audio, stage_audio, ArchiveFormat, StagedAudio};
+use crate::whisper::{TranscriptionResult, TranscriptionSegment};
+
+const DATABASE_FILE: &str = "history.db";
+
+// Upper bound for list and search page sizes
+const MAX_PAGE_SIZE: usize = 500;
+
+/// Schema migrations, applied in order. The index + 1 of the last applied
+/// migration is stored in `PRAGMA user_version`; only ever append.
+const MIGRATIONS: &[&str] = &[
+    // 1: recordings, transcripts with their segments, enrichments, transcript search
+    r#"
+    CREATE TABLE recordings (
+        id TEXT PRIMARY KEY,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL,
+        title TEXT,
+        duration_ms INTEGER,
+        starred INTEGER NOT NULL DEFAULT 0,
+        tags TEXT NOT NULL DEFAULT '[]',
+        notes TEXT
+    );
+    CREATE INDEX recordings_created_at ON recordings(created_at);
+
+    CREATE TABLE transcripts (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        recording_id TEXT NOT NULL REFERENCES recordings(id) ON DELETE CASCADE,
+        created_at INTEGER NOT NULL,
+        text TEXT NOT NULL,
+        language TEXT,
+        model TEXT
+    );
+    CREATE INDEX transcripts_recording_id ON transcripts(recording_id);
+
+    CREATE TABLE segments (
+        transcript_id INTEGER NOT NULL REFERENCES transcripts(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        t0 INTEGER NOT NULL,
+        t1 INTEGER NOT NULL,
+        text TEXT NOT NULL,
+        no_speech_probability REAL NOT NULL,
+        PRIMARY KEY (transcript_id, position)
+    );
+
+    CREATE TABLE enrichments (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        recording_id TEXT NOT NULL REFERENCES recordings(id) ON DELETE CASCADE,
+        created_at INTEGER NOT NULL,
+        kind TEXT NOT NULL,
+        content TEXT NOT NULL,
+        model TEXT
+    );
+    CREATE INDEX enrichments_recording_id ON enrichments(recording_id);
+
+    CREATE VIRTUAL TABLE transcripts_fts USING fts5(
+        text, content='transcripts', content_rowid='id'
+    );
+    CREATE TRIGGER transcripts_ai AFTER INSERT ON transcripts BEGIN
+        INSERT INTO transcripts_fts(rowid, text) VALUES (new.id, new.text);
+    END;
+    CREATE TRIGGER transcripts_ad AFTER DELETE ON transcripts BEGIN
+        INSERT INTO transcripts_fts(transcripts_fts, rowid, text) VALUES ('delete', old.id, old.text);
+    END;
+    CREATE TRIGGER transcripts_au AFTER UPDATE ON transcripts BEGIN
+        INSERT INTO transcripts_fts(transcripts_fts, rowid, text) VALUES ('delete', old.id, old.text);
+        INSERT INTO transcripts_fts(rowid, text) VALUES (new.id, new.text);
+    END;
+    "#,
+    // 2: archived audio, stored in the recordings directory
+    r#"
+    ALTER TABLE recordings ADD COLUMN audio_file TEXT;
+    ALTER TABLE recordings ADD COLUMN audio_size INTEGER;
+    "#,
+    // 3: word timing and confidence, as a JSON array per segment
+    r#"
+    ALTER TABLE segments ADD COLUMN words TEXT;
+    "#,
+];
+
+/// A recording in the history, without its transcripts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecording {
+    pub id: String,
+    pub created_at: i64, // Unix time in milliseconds
+    pub updated_at: i64,
+    pub title: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub starred: bool,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+    pub audio_format: Option<ArchiveFormat>, // None when no audio is archived
+    pub audio_size: Option<i64>,             // Archived audio size in bytes
+}
+
+/// Fields accepted when creating a recording
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NewRecording {
+    pub title: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub starred: bool,
+    pub tags: Vec<String>,
+    pub notes: Option<String>,
+}
+
+/// Partial update of a recording; absent fields are left unchanged and a
+/// null title or notes clears the field
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingUpdate {
+    #[serde(deserialize_with = "nullable", skip_serializing_if = "Option::is_none")]
+    pub title: Option<Option<String>>,
+    pub starred: Option<bool>,
+    pub tags: Option<Vec<String>>,
+    #[serde(deserialize_with = "nullable", skip_serializing_if = "Option::is_none")]
+    pub notes: Option<Option<String>>,
+}
+
+/// Read a present field as `Some`, even when it is null, so a missing field
+/// (left at its default `None`) can be told apart from an explicit null
+fn nullable<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Option<String>>, D::Error> {
+    Option::<String>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryTranscript {
+    pub id: i64,
+    pub recording_id: String,
+    pub created_at: i64,
+    pub text: String,
+    pub language: Option<String>,
+    pub model: Option<String>, // Model variant that produced the transcript
+    pub segments: Vec<TranscriptionSegment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEnrichment {
+    pub id: i64,
+    pub recording_id: String,
+    pub created_at: i64,
+    pub kind: String, // Enrichment type, e.g. "summary" or "action_items"
+    pub content: String,
+    pub model: Option<String>,
+}
+
+/// A recording with everything stored for it, newest transcript first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingDetail {
+    pub recording: HistoryRecording,
+    pub transcripts: Vec<HistoryTranscript>,
+    pub enrichments: Vec<HistoryEnrichment>,
+}
+
+/// A transcript matching a full-text search, best match first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub recording_id: String,
+    pub transcript_id: i64,
+    pub snippet: String, // Matching text with hits wrapped in [ and ]
+    pub rank: f64,       // BM25 score, lower is better
+}
+
+lazy_static::lazy_static! {
+    static ref DATABASE: Mutex<Option<Connection>> = Mutex::new(None);
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn database_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(DATABASE_FILE))
+}
+
+/// Bring the schema up to date, one transaction per migration
+fn migrate(connection: &mut Connection) -> Result<(), String> {
+    let version: usize = connection
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read schema version: {}", e))?;
+
+    if version > MIGRATIONS.len() {
+        return Err(format!(
+            "History database schema version {} is newer than this app supports ({})",
+            version,
+            MIGRATIONS.len()
+        ));
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction = connection
+            .transaction()
+            .map_err(|e| format!("Failed to start migration: {}", e))?;
+        transaction
+            .execute_batch(migration)
+            .map_err(|e| format!("Migration {} failed: {}", index + 1, e))?;
+        transaction
+            .pragma_update(None, "user_version", index + 1)
+            .map_err(|e| format!("Failed to record schema version: {}", e))?;
+        transaction
+            .commit()
+            .map_err(|e| format!("Failed to commit migration: {}", e))?;
+    }
+
+    Ok(())
+}
+
+fn open_database(app_handle: &AppHandle) -> Result<Connection, String> {
+    let mut connection = Connection::open(database_path(app_handle)?)
+        .map_err(|e| format!("Failed to open history database: {}", e))?;
+    connection
+        .execute_batch("PRAGMA foreign_keys = ON; PRAGMA journal_mode = WAL;")
+        .map_err(|e| format!("Failed to configure history database: {}", e))?;
+    migrate(&mut connection)?;
+    Ok(connection)
+}
+
+/// Run `f` against the history database, opening and migrating it on first use
+pub(crate) fn with_database<T>(
+    app_handle: &AppHandle,
+    f: impl FnOnce(&mut Connection) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut database = DATABASE.lock().unwrap();
+    if database.is_none() {
+        *database = Some(open_database(app_handle)?);
+    }
+    f(database.as_mut().unwrap())
+}
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("History database error: {}", e)
+}
+
+fn recording_from_row(row: &Row) -> rusqlite::Result<HistoryRecording> {
+    let tags: String = row.get("tags")?;
+    let audio_file: Option<String> = row.get("audio_file")?;
+    Ok(HistoryRecording {
+        id: row.get("id")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+        title: row.get("title")?,
+        duration_ms: row.get("duration_ms")?,
+        starred: row.get("starred")?,
+        tags: serde_json::from_str(&tags).unwrap_or_default(),
+        notes: row.get("notes")?,
+        audio_format: audio_file.as_deref().and_then(ArchiveFormat::from_file_name),
+        audio_size: row.get("audio_size")?,
+    })
+}
+
+fn enrichment_from_row(row: &Row) -> rusqlite::Result<HistoryEnrichment> {
+    Ok(HistoryEnrichment {
+        id: row.get("id")?,
+        recording_id: row.get("recording_id")?,
+        created_at: row.get("created_at")?,
+        kind: row.get("kind")?,
+        content: row.get("content")?,
+        model: row.get("model")?,
+    })
+}
+
+fn tags_json(tags: &[String]) -> String {
+    serde_json::to_string(tags).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn find_recording(connection: &Connection, id: &str) -> Result<Option<HistoryRecording>, String> {
+    connection
+        .query_row("SELECT * FROM recordings WHERE id = ?1", [id], recording_from_row)
+        .optional()
+        .map_err(db_error)
+}
+
+pub(crate) fn insert_recording(connection: &Connection, recording: &NewRecording) -> Result<HistoryRecording, String> {
+    let now = now_ms();
+    let record = HistoryRecording {
+        id: uuid::Uuid::new_v4().to_string(),
+        created_at: now,
+        updated_at: now,
+        title: recording.title.clone(),
+        duration_ms: recording.duration_ms,
+        starred: recording.starred,
+        tags: recording.tags.clone(),
+        notes: recording.notes.clone(),
+        audio_format: None,
+        audio_size: None,
+    };
+
+    connection
+        .execute(
+            "INSERT INTO recordings (id, created_at, updated_at, title, duration_ms, starred, tags, notes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                record.id,
+                record.created_at,
+                record.updated_at,
+                record.title,
+                record.duration_ms,
+                record.starred,
+                tags_json(&record.tags),
+                record.notes,
+            ],
+        )
+        .map_err(db_error)?;
+
+    Ok(record)
+}
+
+pub(crate) fn insert_transcript(
+    connection: &mut Connection,
+    recording_id: &str,
+    result: &TranscriptionResult,
+    model: Option<&str>,
+) -> Result<HistoryTranscript, String> {
+    let transaction = connection.transaction().map_err(db_error)?;
+    let transcript = write_transcript(&transaction, recording_id, result, model)?;
+    transaction.commit().map_err(db_error)?;
+
+    Ok(transcript)
+}
+
+/// Insert a transcript and its segments; the caller provides the transaction
+fn write_transcript(
+    transaction: &Connection,
+    recording_id: &str,
+    result: &TranscriptionResult,
+    model: Option<&str>,
+) -> Result<HistoryTranscript, String> {
+    let created_at = now_ms();
+
+    transaction
+        .execute(
+            "INSERT INTO transcripts (recording_id, created_at, text, language, model) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![recording_id, created_at, result.text, result.language, model],
+        )
+        .map_err(db_error)?;
+    let id = transaction.last_insert_rowid();
+
+    {
+        let mut statement = transaction
+            .prepare(
+                "INSERT INTO segments (transcript_id, position, t0, t1, text, no_speech_probability, words)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )
+            .map_err(db_error)?;
+        for (position, segment) in result.segments.iter().enumerate() {
+            let words = if segment.words.is_empty() {
+                None
+            } else {
+                Some(serde_json::to_string(&segment.words).map_err(|e| format!("Failed to serialize words: {}", e))?)
+            };
+            statement
+                .execute(params![
+                    id,
+                    position as i64,
+                    segment.t0,
+                    segment.t1,
+                    segment.text,
+                    segment.no_speech_probability,
+                    words,
+                ])
+                .map_err(db_error)?;
+        }
+    }
+
+    transaction
+        .execute("UPDATE recordings SET updated_at = ?1 WHERE id = ?2", params![created_at, recording_id])
+        .map_err(db_error)?;
+
+    Ok(HistoryTranscript {
+        id,
+        recording_id: recording_id.to_string(),
+        created_at,
+        text: result.text.clone(),
+        language: Some(result.language.clone()),
+        model: model.map(str::to_string),
+        segments: result.segments.clone(),
+    })
+}
+
+fn transcript_from_row(row: &Row) -> rusqlite::Result<HistoryTranscript> {
+    Ok(HistoryTranscript {
+        id: row.get("id")?,
+        recording_id: row.get("recording_id")?,
+        created_at: row.get("created_at")?,
+        text: row.get("text")?,
+        language: row.get("language")?,
+        model: row.get("model")?,
+        segments: Vec::new(),
+    })
+}
+
+fn load_segments(connection: &Connection, transcript_id: i64) -> Result<Vec<TranscriptionSegment>, String> {
+    let mut statement = connection
+        .prepare_cached(
+            "SELECT t0, t1, text, no_speech_probability, words FROM segments
+             WHERE transcript_id = ?1 ORDER BY position",
+        )
+        .map_err(db_error)?;
+    let segments = statement
+        .query_map([transcript_id], |row| {
+            let words: Option<String> = row.get(4)?;
+            Ok(TranscriptionSegment {
+                t0: row.get(0)?,
+                t1: row.get(1)?,
+                text: row.get(2)?,
+                no_speech_probability: row.get(3)?,
+                words: words.and_then(|words| serde_json::from_str(&words).ok()).unwrap_or_default(),
+            })
+        })
+        .map_err(db_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_error)?;
+    Ok(segments)
+}
+
+fn load_transcripts(connection: &Connection, recording_id: &str) -> Result<Vec<HistoryTranscript>, String> {
+    let mut statement = connection
+        .prepare("SELECT * FROM transcripts WHERE recording_id = ?1 ORDER BY created_at DESC, id DESC")
+        .map_err(db_error)?;
+    let mut transcripts = statement
+        .query_map([recording_id], transcript_from_row)
+        .map_err(db_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_error)?;
+
+    for transcript in transcripts.iter_mut() {
+        transcript.segments = load_segments(connection, transcript.id)?;
+    }
+
+    Ok(transcripts)
+}
+
+/// A single stored transcript with its segments
+pub(crate) fn load_transcript(connection: &Connection, id: i64) -> Result<Option<HistoryTranscript>, String> {
+    let transcript = connection
+        .query_row("SELECT * FROM transcripts WHERE id = ?1", [id], transcript_from_row)
+        .optional()
+        .map_err(db_error)?;
+    match transcript {
+        Some(mut transcript) => {
+            transcript.segments = load_segments(connection, transcript.id)?;
+            Ok(Some(transcript))
+        }
+        None => Ok(None),
+    }
+}
+
+fn load_enrichments(connection: &Connection, recording_id: &str) -> Result<Vec<HistoryEnrichment>, String> {
+    let mut statement = connection
+        .prepare("SELECT * FROM enrichments WHERE recording_id = ?1 ORDER BY created_at DESC, id DESC")
+        .map_err(db_error)?;
+    let enrichments = statement
+        .query_map([recording_id], enrichment_from_row)
+        .map_err(db_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_error)?;
+    Ok(enrichments)
+}
+
+/// Turn free text into an FTS5 query: every word must match, the last one as a prefix
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!("{}*", terms.join(" ")))
+}
+
+#[tauri::command]
+pub async fn create_recording(recording: NewRecording, app_handle: AppHandle) -> Result<HistoryRecording, String> {
+    with_database(&app_handle, |connection| insert_recording(connection, &recording))
+}
+
+#[tauri::command]
+pub async fn get_recording(id: String, app_handle: AppHandle) -> Result<Option<RecordingDetail>, String> {
+    with_database(&app_handle, |connection| {
+        let Some(recording) = find_recording(connection, &id)? else {
+            return Ok(None);
+        };
+        Ok(Some(RecordingDetail {
+            transcripts: load_transcripts(connection, &id)?,
+            enrichments: load_enrichments(connection, &id)?,
+            recording,
+        }))
+    })
+}
+
+/// Recordings, newest first
+#[tauri::command]
+pub async fn list_recordings(
+    limit: Option<usize>,
+    offset: Option<usize>,
+    starred_only: Option<bool>,
+    app_handle: AppHandle,
+) -> Result<Vec<HistoryRecording>, String> {
+    let limit = limit.unwrap_or(50).min(MAX_PAGE_SIZE) as i64;
+    let offset = offset.unwrap_or(0) as i64;
+    let starred_only = starred_only.unwrap_or(false);
+
+    with_database(&app_handle, |connection| {
+        let mut statement = connection
+            .prepare(
+                "SELECT * FROM recordings WHERE (?1 = 0 OR starred = 1)
+                 ORDER BY created_at DESC LIMIT ?2 OFFSET ?3",
+            )
+            .map_err(db_error)?;
+        let recordings = statement
+            .query_map(params![starred_only, limit, offset], recording_from_row)
+            .map_err(db_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_error)?;
+        Ok(recordings)
+    })
+}
+
+fn apply_update(connection: &Connection, id: &str, update: RecordingUpdate) -> Result<HistoryRecording, String> {
+    let mut recording = find_recording(connection, id)?
+        .ok_or_else(|| format!("Recording not found: {}", id))?;
+
+    if let Some(title) = update.title {
+        recording.title = title;
+    }
+    if let Some(starred) = update.starred {
+        recording.starred = starred;
+    }
+    if let Some(tags) = update.tags {
+        recording.tags = tags;
+    }
+    if let Some(notes) = update.notes {
+        recording.notes = notes;
+    }
+    recording.updated_at = now_ms();
+
+    connection
+        .execute(
+            "UPDATE recordings SET title = ?1, starred = ?2, tags = ?3, notes = ?4, updated_at = ?5 WHERE id = ?6",
+            params![
+                recording.title,
+                recording.starred,
+                tags_json(&recording.tags),
+                recording.notes,
+                recording.updated_at,
+                recording.id,
+            ],
+        )
+        .map_err(db_error)?;
+
+    Ok(recording)
+}
+
+#[tauri::command]
+pub async fn update_recording(
+    id: String,
+    update: RecordingUpdate,
+    app_handle: AppHandle,
+) -> Result<HistoryRecording, String> {
+    with_database(&app_handle, |connection| apply_update(connection, &id, update))
+}
+
+/// Delete a recording together with its transcripts, segments, enrichments and audio
+#[tauri::command]
+pub async fn delete_recording(id: String, app_handle: AppHandle) -> Result<(), String> {
+    let audio_file = with_database(&app_handle, |connection| {
+        let audio_file: Option<String> = connection
+            .query_row("SELECT audio_file FROM recordings WHERE id = ?1", [&id], |row| row.get(0))
+            .optional()
+            .map_err(db_error)?
+            .ok_or_else(|| format!("Recording not found: {}", id))?;
+        connection
+            .execute("DELETE FROM recordings WHERE id = ?1", [&id])
+            .map_err(db_error)?;
+        Ok(audio_file)
+    })?;
+
+    // A file left behind is picked up by the next archive cleanup
+    if let Some(audio_file) = audio_file {
+        if let Err(e) = remove_archived_audio(&app_handle, &audio_file) {
+            eprintln!("Failed to delete audio of recording {}: {}", id, e);
+        }
+    }
+    Ok(())
+}
+
+/// Store a transcription result for a recording
+#[tauri::command]
+pub async fn add_transcript(
+    recording_id: String,
+    result: TranscriptionResult,
+    model: Option<String>,
+    app_handle: AppHandle,
+) -> Result<HistoryTranscript, String> {
+    with_database(&app_handle, |connection| {
+        if find_recording(connection, &recording_id)?.is_none() {
+            return Err(format!("Recording not found: {}", recording_id));
+        }
+        insert_transcript(connection, &recording_id, &result, model.as_deref())
+    })
+}
+
+#[tauri::command]
+pub async fn delete_transcript(id: i64, app_handle: AppHandle) -> Result<(), String> {
+    with_database(&app_handle, |connection| {
+        let deleted = connection
+            .execute("DELETE FROM transcripts WHERE id = ?1", [id])
+            .map_err(db_error)?;
+        if deleted == 0 {
+            return Err(format!("Transcript not found: {}", id));
+        }
+        Ok(())
+    })
+}
+
+/// Store an LLM enrichment (summary, action items, ...) for a recording
+#[tauri::command]
+pub async fn add_enrichment(
+    recording_id: String,
+    kind: String,
+    content: String,
+    model: Option<String>,
+    app_handle: AppHandle,
+) -> Result<HistoryEnrichment, String> {
+    with_database(&app_handle, |connection| {
+        let created_at = now_ms();
+        connection
+            .execute(
+                "INSERT INTO enrichments (recording_id, created_at, kind, content, model) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![recording_id, created_at, kind, content, model],
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::SqliteFailure(error, _)
+                    if error.code == rusqlite::ErrorCode::ConstraintViolation =>
+                {
+                    format!("Recording not found: {}", recording_id)
+                }
+                e => db_error(e),
+            })?;
+
+        Ok(HistoryEnrichment {
+            id: connection.last_insert_rowid(),
+            recording_id,
+            created_at,
+            kind,
+            content,
+            model,
+        })
+    })
+}
+
+#[tauri::command]
+pub async fn delete_enrichment(id: i64, app_handle: AppHandle) -> Result<(), String> {
+    with_database(&app_handle, |connection| {
+        let deleted = connection
+            .execute("DELETE FROM enrichments WHERE id = ?1", [id])
+            .map_err(db_error)?;
+        if deleted == 0 {
+            return Err(format!("Enrichment not found: {}", id));
+        }
+        Ok(())
+    })
+}
+
+/// Full-text search over transcripts. Every word of `query` must occur;
+/// the last word also matches as a prefix so search-as-you-type works.
+#[tauri::command]
+pub async fn search_transcripts(
+    query: String,
+    limit: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<Vec<SearchHit>, String> {
+    let Some(fts_query) = fts_query(&query) else {
+        return Ok(Vec::new());
+    };
+    let limit = limit.unwrap_or(50).min(MAX_PAGE_SIZE) as i64;
+
+    with_database(&app_handle, |connection| search(connection, &fts_query, limit))
+}
+
+fn search(connection: &Connection, fts_query: &str, limit: i64) -> Result<Vec<SearchHit>, String> {
+    let mut statement = connection
+        .prepare(
+            "SELECT t.recording_id, t.id, snippet(transcripts_fts, 0, '[', ']', '…', 12), transcripts_fts.rank
+             FROM transcripts_fts JOIN transcripts t ON t.id = transcripts_fts.rowid
+             WHERE transcripts_fts MATCH ?1
+             ORDER BY transcripts_fts.rank LIMIT ?2",
+        )
+        .map_err(db_error)?;
+    let hits = statement
+        .query_map(params![fts_query, limit], |row| {
+            Ok(SearchHit {
+                recording_id: row.get(0)?,
+                transcript_id: row.get(1)?,
+                snippet: row.get(2)?,
+                rank: row.get(3)?,
+            })
+        })
+        .map_err(db_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_error)?;
+    Ok(hits)
+}
+
+/// Store a finished hotkey dictation as a new recording with its transcript
+/// and, if given, its audio. Returns the id of the new recording.
+///
+/// The audio is written under a temporary name first and moved into place
+/// inside the database transaction, so a failure leaves neither a row without
+/// its file nor a file without its row. Audio that cannot be encoded is
+/// reported and the transcript saved without it.
+pub(crate) fn save_dictation(
+    app_handle: &AppHandle,
+    duration_ms: u64,
+    result: &TranscriptionResult,
+    model: Option<&str>,
+    audio: Option<&[f32]>,
+) -> Result<String, String> {
+    let staged = audio.and_then(|samples| {
+        stage_audio(app_handle, samples)
+            .map_err(|e| eprintln!("Failed to archive dictation audio: {}", e))
+            .ok()
+    });
+
+    with_database(app_handle, |connection| write_dictation(connection, duration_ms, result, model, staged))
+}
+
+/// Insert the recording, its transcript and its staged audio in one
+/// transaction; nothing is kept if any step fails
+fn write_dictation(
+    connection: &mut Connection,
+    duration_ms: u64,
+    result: &TranscriptionResult,
+    model: Option<&str>,
+    staged: Option<StagedAudio>,
+) -> Result<String, String> {
+    let transaction = connection.transaction().map_err(db_error)?;
+    let recording = insert_recording(&transaction, &NewRecording {
+        duration_ms: Some(duration_ms as i64),
+        ..NewRecording::default()
+    })?;
+    write_transcript(&transaction, &recording.id, result, model)?;
+
+    let stored = match staged {
+        Some(staged) => {
+            transaction
+                .execute(
+                    "UPDATE recordings SET audio_file = ?1, audio_size = ?2 WHERE id = ?3",
+                    params![staged.file_name(&recording.id), staged.size() as i64, recording.id],
+                )
+                .map_err(db_error)?;
+            Some(staged.store(&recording.id)?)
+        }
+        None => None,
+    };
+
+    if let Err(e) = transaction.commit() {
+        if let Some(path) = stored {
+            let _ = std::fs::remove_file(path);
+        }
+        return Err(db_error(e));
+    }
+
+    Ok(recording.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::whisper::TranscriptionWord;
+
+    fn open_memory() -> Connection {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        migrate(&mut connection).unwrap();
+        connection
+    }
+
+    fn result(text: &str, no_speech_probability: f32) -> TranscriptionResult {
+        TranscriptionResult {
+            text: text.to_string(),
+            segments: vec![TranscriptionSegment {
+                t0: 0,
+                t1: 1500,
+                text: text.to_string(),
+                no_speech_probability,
+                words: vec![TranscriptionWord {
+                    text: "The".to_string(),
+                    t0: 0,
+                    t1: 200,
+                    probability: 0.9,
+                }],
+            }],
+            language: "en".to_string(),
+            language_probability: None,
+        }
+    }
+
+    fn count(connection: &Connection, table: &str) -> i64 {
+        connection
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn migrations_reach_the_latest_version_once() {
+        let mut connection = open_memory();
+        let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+
+        // Already up to date: nothing is applied twice
+        migrate(&mut connection).unwrap();
+
+        connection.pragma_update(None, "user_version", MIGRATIONS.len() + 1).unwrap();
+        assert!(migrate(&mut connection).unwrap_err().contains("newer"));
+    }
+
+    #[test]
+    fn transcripts_are_stored_and_searchable() {
+        let mut connection = open_memory();
+        let recording = insert_recording(&connection, &NewRecording::default()).unwrap();
+        let transcript = insert_transcript(
+            &mut connection,
+            &recording.id,
+            &result("The quick brown fox", 0.1),
+            Some("base.en"),
+        )
+        .unwrap();
+
+        let stored = load_transcript(&connection, transcript.id).unwrap().unwrap();
+        assert_eq!(stored.model.as_deref(), Some("base.en"));
+        assert_eq!(stored.segments.len(), 1);
+        assert_eq!(stored.segments[0].words[0].text, "The");
+
+        // The last word matches as a prefix
+        let hits = search(&connection, &fts_query("quick bro").unwrap(), 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].recording_id, recording.id);
+        assert!(hits[0].snippet.contains("[quick]"));
+        assert!(search(&connection, &fts_query("quick cat").unwrap(), 10).unwrap().is_empty());
+
+        connection.execute("DELETE FROM recordings WHERE id = ?1", [&recording.id]).unwrap();
+        assert_eq!(count(&connection, "segments"), 0);
+        assert!(search(&connection, &fts_query("quick").unwrap(), 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn search_terms_are_quoted() {
+        assert_eq!(fts_query("  "), None);
+        assert_eq!(fts_query("say \"hi\" OR"), Some("\"say\" \"\"\"hi\"\"\" \"OR\"*".to_string()));
+    }
+
+    #[test]
+    fn failed_dictation_is_rolled_back() {
+        let mut connection = open_memory();
+
+        // SQLite stores NaN as NULL, which the segments table rejects
+        let error = write_dictation(&mut connection, 1500, &result("Lost words", f32::NAN), None, None);
+        assert!(error.is_err());
+        assert_eq!(count(&connection, "recordings"), 0);
+        assert_eq!(count(&connection, "transcripts"), 0);
+        assert!(search(&connection, &fts_query("lost").unwrap(), 10).unwrap().is_empty());
+
+        let id = write_dictation(&mut connection, 1500, &result("Kept words", 0.1), None, None).unwrap();
+        let recording = find_recording(&connection, &id).unwrap().unwrap();
+        assert_eq!(recording.duration_ms, Some(1500));
+        assert_eq!(load_transcripts(&connection, &id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn update_leaves_absent_fields_and_clears_null_ones() {
+        let connection = open_memory();
+        let recording = insert_recording(&connection, &NewRecording {
+            title: Some("Standup".to_string()),
+            notes: Some("Ask about the release".to_string()),
+            ..NewRecording::default()
+        })
+        .unwrap();
+
+        let update: RecordingUpdate = serde_json::from_str(r#"{"starred": true}"#).unwrap();
+        let updated = apply_update(&connection, &recording.id, update).unwrap();
+        assert_eq!(updated.title.as_deref(), Some("Standup"));
+        assert!(updated.starred);
+
+        let update: RecordingUpdate = serde_json::from_str(r#"{"title": null, "notes": "Done"}"#).unwrap();
+        apply_update(&connection, &recording.id, update).unwrap();
+        let stored = find_recording(&connection, &recording.id).unwrap().unwrap();
+        assert_eq!(stored.title, None);
+        assert_eq!(stored.notes.as_deref(), Some("Done"));
+        assert!(stored.starred);
+
+        assert!(apply_update(&connection, "missing", RecordingUpdate::default()).is_err());
+    }
+}
@@ -2,8 +2,25 @@
 // This is required for the mobile targets
 
 mod whisper;
+mod model_pool;
+mod transcription_jobs;
+mod audio_conditioning;
+mod audio_decode;
+mod audio_capture;
+mod dictation;
+mod shortcuts;
+mod output_sink;
+mod history;
+mod audio_archive;
+mod transcript_export;
 mod file_utils;
+mod downloads;
+mod model_catalog;
+mod custom_models;
 mod system_info;
+mod vad;
+mod decoding;
+mod settings_store;
 
 #[cfg(mobile)]
 mod mobile;
@@ -12,5 +29,21 @@ mod mobile;
 pub use mobile::*;
 
 pub use whisper::*;
+pub use model_pool::*;
+pub use transcription_jobs::*;
+pub use audio_conditioning::*;
+pub use audio_decode::*;
+pub use audio_capture::*;
+pub use dictation::*;
+pub use shortcuts::*;
+pub use output_sink::*;
+pub use history::*;
+pub use audio_archive::*;
+pub use transcript_export::*;
 pub use file_utils::*;
+pub use downloads::*;
+pub use model_catalog::*;
+pub use custom_models::*;
 pub use system_info::*;
+pub use vad::*;
+pub use decoding::*;
@@ -2,26 +2,23 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use tauri::{
-    AppHandle, CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
+    AppHandle, CustomMenuItem, Emitter, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
     SystemTrayMenuItem, WindowEvent,
 };
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 use tauri_plugin_autostart::MacosLauncher;
 
 // Commands that can be invoked from the frontend
 #[tauri::command]
 fn toggle_recording(app_handle: AppHandle) -> Result<(), String> {
-    // Emit event to frontend to toggle recording
+    // Record natively so the hotkey works while the window is hidden; the
+    // state arrives via `dictation-state` and the transcript via `dictation-result`
+    voice_intelligence_lib::request_dictation_toggle(&app_handle);
+
+    // Still tell the webview, whose recording flow listens for the toggle
     app_handle
         .emit("toggle-recording", ())
         .map_err(|e| e.to_string())?;
-    
-    // Show window if hidden
-    if let Some(window) = app_handle.get_webview_window("main") {
-        window.show().map_err(|e| e.to_string())?;
-        window.set_focus().map_err(|e| e.to_string())?;
-    }
-    
+
     Ok(())
 }
 
@@ -95,20 +92,6 @@ fn handle_system_tray_event(app: &AppHandle, event: SystemTrayEvent) {
     }
 }
 
-fn setup_global_shortcut(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    // Register global shortcut for recording
-    let shortcut = "CommandOrControl+Shift+Space".parse::<Shortcut>()?;
-    let app_handle = app.clone();
-    
-    app.global_shortcut().on_shortcut(shortcut, move || {
-        let _ = toggle_recording(app_handle.clone());
-    })?;
-    
-    app.global_shortcut().register(shortcut)?;
-    
-    Ok(())
-}
-
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
@@ -117,10 +100,18 @@ fn main() {
         .system_tray(create_system_tray())
         .on_system_tray_event(handle_system_tray_event)
         .setup(|app| {
-            // Setup global shortcut
-            if let Err(e) = setup_global_shortcut(&app.handle()) {
-                eprintln!("Failed to setup global shortcut: {}", e);
-            }
+            // Restore hotkey recording settings before any hotkey can fire
+            voice_intelligence_lib::init_dictation_settings(&app.handle());
+            voice_intelligence_lib::init_output_settings(&app.handle());
+
+            // Register the configured global shortcuts
+            voice_intelligence_lib::setup_shortcuts(&app.handle());
+
+            // Make imported models selectable
+            voice_intelligence_lib::init_custom_models(&app.handle());
+
+            // Apply the audio retention policy now and periodically
+            voice_intelligence_lib::start_archive_cleanup(&app.handle());
             
             // Handle window close event (minimize to tray instead of closing)
             if let Some(window) = app.get_webview_window("main") {
@@ -142,13 +133,70 @@ fn main() {
             voice_intelligence_lib::load_whisper_model,
             voice_intelligence_lib::unload_whisper_model,
             voice_intelligence_lib::transcribe_audio,
+            voice_intelligence_lib::transcribe_audio_segments,
+            voice_intelligence_lib::transcribe_file,
+            voice_intelligence_lib::start_transcription_session,
+            voice_intelligence_lib::push_transcription_audio,
+            voice_intelligence_lib::finish_transcription_session,
+            voice_intelligence_lib::cancel_transcription_session,
+            voice_intelligence_lib::start_transcription,
+            voice_intelligence_lib::cancel_transcription,
+            voice_intelligence_lib::get_transcription_job,
+            voice_intelligence_lib::list_transcription_jobs,
+            voice_intelligence_lib::get_transcription_queue_status,
+            voice_intelligence_lib::set_transcription_concurrency,
             voice_intelligence_lib::get_whisper_model_status,
+            voice_intelligence_lib::get_model_pool_status,
+            voice_intelligence_lib::set_model_memory_budget,
+            voice_intelligence_lib::list_input_devices,
+            voice_intelligence_lib::select_input_device,
+            voice_intelligence_lib::get_capture_status,
+            voice_intelligence_lib::start_capture,
+            voice_intelligence_lib::stop_capture,
+            voice_intelligence_lib::transcribe_last_capture,
+            voice_intelligence_lib::get_dictation_settings,
+            voice_intelligence_lib::get_output_settings,
+            voice_intelligence_lib::set_output_settings,
+            voice_intelligence_lib::send_text_to_focused_app,
+            voice_intelligence_lib::list_shortcuts,
+            voice_intelligence_lib::register_shortcut,
+            voice_intelligence_lib::rebind_shortcut,
+            voice_intelligence_lib::unregister_shortcut,
+            voice_intelligence_lib::set_dictation_settings,
+            voice_intelligence_lib::create_recording,
+            voice_intelligence_lib::get_recording,
+            voice_intelligence_lib::list_recordings,
+            voice_intelligence_lib::update_recording,
+            voice_intelligence_lib::delete_recording,
+            voice_intelligence_lib::add_transcript,
+            voice_intelligence_lib::delete_transcript,
+            voice_intelligence_lib::add_enrichment,
+            voice_intelligence_lib::delete_enrichment,
+            voice_intelligence_lib::search_transcripts,
+            voice_intelligence_lib::get_archive_settings,
+            voice_intelligence_lib::set_archive_settings,
+            voice_intelligence_lib::archive_audio_file,
+            voice_intelligence_lib::get_recording_audio,
+            voice_intelligence_lib::get_recording_audio_path,
+            voice_intelligence_lib::run_archive_cleanup,
+            voice_intelligence_lib::export_transcript,
+            voice_intelligence_lib::export_history_transcript,
+            voice_intelligence_lib::list_decoding_presets,
             voice_intelligence_lib::file_exists,
             voice_intelligence_lib::delete_file,
             voice_intelligence_lib::calculate_file_checksum,
             voice_intelligence_lib::get_available_disk_space,
             voice_intelligence_lib::get_models_directory,
-            voice_intelligence_lib::download_model,
+            voice_intelligence_lib::get_recordings_directory,
+            voice_intelligence_lib::list_available_models,
+            voice_intelligence_lib::download_model_variant,
+            voice_intelligence_lib::import_model,
+            voice_intelligence_lib::list_custom_models,
+            voice_intelligence_lib::remove_custom_model,
+            voice_intelligence_lib::pause_download,
+            voice_intelligence_lib::resume_download,
+            voice_intelligence_lib::cancel_download,
+            voice_intelligence_lib::list_downloads,
             voice_intelligence_lib::get_system_memory,
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,472 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::custom_models::{custom_model, custom_model_filename};
+use crate::downloads::spawn_download;
+use crate::file_utils::models_directory;
+use crate::whisper::ModelVariant;
+
+const MB: u64 = 1024 * 1024;
+
+// Hosts serving Hugging Face repositories, tried in order
+const MIRRORS: [&str; 2] = ["https://huggingface.co", "https://hf-mirror.com"];
+
+const WHISPER_CPP_REPOSITORY: &str = "ggerganov/whisper.cpp";
+const DISTIL_LARGE_REPOSITORY: &str = "distil-whisper/distil-large-v3-ggml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LanguageSupport {
+    Multilingual,
+    EnglishOnly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelFamily {
+    Whisper, // OpenAI's original checkpoints
+    Turbo,   // large-v3 with a pruned decoder
+    Distil,  // Distilled by Hugging Face, English only
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelSize {
+    Tiny,
+    Base,
+    Small,
+    Medium,
+    Large,
+}
+
+/// Weight format of a ggml model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Quantization {
+    F32,
+    F16,
+    Q4_0,
+    Q4_1,
+    Q5_0,
+    Q5_1,
+    Q8_0,
+}
+
+/// A model the app knows how to download and verify
+#[derive(Debug, Clone)]
+pub struct CatalogModel {
+    pub variant: ModelVariant,
+    pub filename: &'static str,
+    pub repository: &'static str, // Hugging Face repository holding the file
+    pub family: ModelFamily,
+    pub model_size: ModelSize,
+    pub quantization: Quantization,
+    pub languages: LanguageSupport,
+    pub size: u64,               // bytes
+    pub sha256: &'static str,
+    pub memory_requirement: u64, // Model plus one inference state, in bytes
+}
+
+impl CatalogModel {
+    /// Download URLs, preferred mirror first
+    pub fn mirrors(&self) -> Vec<String> {
+        MIRRORS
+            .iter()
+            .map(|mirror| format!("{}/{}/resolve/main/{}", mirror, self.repository, self.filename))
+            .collect()
+    }
+}
+
+// Ordered by size class, then by memory requirement
+static CATALOG: [CatalogModel; 19] = [
+    CatalogModel {
+        variant: ModelVariant::TinyQ5,
+        filename: "ggml-tiny-q5_1.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Tiny,
+        quantization: Quantization::Q5_1,
+        languages: LanguageSupport::Multilingual,
+        size: 32_152_673,
+        sha256: "818710568da3ca15689e31a743197b520007872ff9576237bda97bd1b469c3d7",
+        memory_requirement: 300 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::Tiny,
+        filename: "ggml-tiny.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Tiny,
+        quantization: Quantization::F16,
+        languages: LanguageSupport::Multilingual,
+        size: 77_691_713,
+        sha256: "be07e048e1e599ad46341c8d2a135645097a538221678b7acdd1b1919c6e1b21",
+        memory_requirement: 390 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::TinyEn,
+        filename: "ggml-tiny.en.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Tiny,
+        quantization: Quantization::F16,
+        languages: LanguageSupport::EnglishOnly,
+        size: 77_704_715,
+        sha256: "921e4cf8686fdd993dcd081a5da5b6c365bfde1162e72b08d75ac75289920b1f",
+        memory_requirement: 390 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::BaseQ5,
+        filename: "ggml-base-q5_1.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Base,
+        quantization: Quantization::Q5_1,
+        languages: LanguageSupport::Multilingual,
+        size: 59_707_625,
+        sha256: "422f1ae452ade6f30a004d7e5c6a43195e4433bc370bf23fac9cc591f01a8898",
+        memory_requirement: 350 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::BaseEnQ5,
+        filename: "ggml-base.en-q5_1.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Base,
+        quantization: Quantization::Q5_1,
+        languages: LanguageSupport::EnglishOnly,
+        size: 59_721_011,
+        sha256: "4baf70dd0d7c4247ba2b81fafd9c01005ac77c2f9ef064e00dcf195d0e2fdd2f",
+        memory_requirement: 350 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::Base,
+        filename: "ggml-base.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Base,
+        quantization: Quantization::F16,
+        languages: LanguageSupport::Multilingual,
+        size: 147_951_465,
+        sha256: "60ed5bc3dd14eea856493d334349b405782ddcaf0028d4b5df4088345fba2efe",
+        memory_requirement: 500 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::BaseEn,
+        filename: "ggml-base.en.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Base,
+        quantization: Quantization::F16,
+        languages: LanguageSupport::EnglishOnly,
+        size: 147_964_211,
+        sha256: "a03779c86df3323075f5e796cb2ce5029f00ec8869eee3fdfb897afe36c6d002",
+        memory_requirement: 500 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::SmallQ5,
+        filename: "ggml-small-q5_1.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Small,
+        quantization: Quantization::Q5_1,
+        languages: LanguageSupport::Multilingual,
+        size: 190_085_487,
+        sha256: "ae85e4a935d7a567bd102fe55afc16bb595bdb618e11b2fc7591bc08120411bb",
+        memory_requirement: 600 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::Small,
+        filename: "ggml-small.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Small,
+        quantization: Quantization::F16,
+        languages: LanguageSupport::Multilingual,
+        size: 487_601_967,
+        sha256: "1be3a9b2063867b937e64e2ec7483364a79917e157fa98c5d94b5c1fffea987b",
+        memory_requirement: 1_000 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::SmallEn,
+        filename: "ggml-small.en.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Small,
+        quantization: Quantization::F16,
+        languages: LanguageSupport::EnglishOnly,
+        size: 487_614_201,
+        sha256: "c6138d6d58ecc8322097e0f987c32f1be8bb0a18532a3f88f734d1bbf9c41e5d",
+        memory_requirement: 1_000 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::MediumQ5,
+        filename: "ggml-medium-q5_0.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Medium,
+        quantization: Quantization::Q5_0,
+        languages: LanguageSupport::Multilingual,
+        size: 539_212_467,
+        sha256: "19fea4b380c3a618ec4723c3eef2eb785ffba0d0538cf43f8f235e7b3b34220f",
+        memory_requirement: 1_300 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::Medium,
+        filename: "ggml-medium.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Medium,
+        quantization: Quantization::F16,
+        languages: LanguageSupport::Multilingual,
+        size: 1_533_763_059,
+        sha256: "6c14d5adee5f86394037b4e4e8b59f1673b6cee10e3cf0b11bbdbee79c156208",
+        memory_requirement: 2_600 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::MediumEn,
+        filename: "ggml-medium.en.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Medium,
+        quantization: Quantization::F16,
+        languages: LanguageSupport::EnglishOnly,
+        size: 1_533_774_781,
+        sha256: "cc37e93478338ec7700281a7ac30a10128929eb8f427dda2e865faa8f6da4356",
+        memory_requirement: 2_600 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::LargeTurboQ5,
+        filename: "ggml-large-v3-turbo-q5_0.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Turbo,
+        model_size: ModelSize::Large,
+        quantization: Quantization::Q5_0,
+        languages: LanguageSupport::Multilingual,
+        size: 574_041_195,
+        sha256: "394221709cd5ad1f40c46e6031ca61bce88931e6e088c188294c6d5a55ffa7e2",
+        memory_requirement: 1_200 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::LargeTurboQ8,
+        filename: "ggml-large-v3-turbo-q8_0.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Turbo,
+        model_size: ModelSize::Large,
+        quantization: Quantization::Q8_0,
+        languages: LanguageSupport::Multilingual,
+        size: 874_188_075,
+        sha256: "317eb69c11673c9de1e1f0d459b253999804ec71ac4c23c17ecf5fbe24e259a1",
+        memory_requirement: 1_500 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::LargeQ5,
+        filename: "ggml-large-v3-q5_0.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Large,
+        quantization: Quantization::Q5_0,
+        languages: LanguageSupport::Multilingual,
+        size: 1_081_140_203,
+        sha256: "d75795ecff3f83b5faa89d1900604ad8c780abd5739fae406de19f23ecd98ad1",
+        memory_requirement: 2_000 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::DistilLarge,
+        filename: "ggml-distil-large-v3.bin",
+        repository: DISTIL_LARGE_REPOSITORY,
+        family: ModelFamily::Distil,
+        model_size: ModelSize::Large,
+        quantization: Quantization::F16,
+        languages: LanguageSupport::EnglishOnly,
+        size: 1_519_521_155,
+        sha256: "2883a11b90fb10ed592d826edeaee7d2929bf1ab985109fe9e1e7b4d2b69a298",
+        memory_requirement: 2_300 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::LargeTurbo,
+        filename: "ggml-large-v3-turbo.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Turbo,
+        model_size: ModelSize::Large,
+        quantization: Quantization::F16,
+        languages: LanguageSupport::Multilingual,
+        size: 1_624_555_275,
+        sha256: "1fc70f774d38eb169993ac391eea357ef47c88757ef72ee5943879b7e8e2bc69",
+        memory_requirement: 2_400 * MB,
+    },
+    CatalogModel {
+        variant: ModelVariant::Large,
+        filename: "ggml-large-v3.bin",
+        repository: WHISPER_CPP_REPOSITORY,
+        family: ModelFamily::Whisper,
+        model_size: ModelSize::Large,
+        quantization: Quantization::F16,
+        languages: LanguageSupport::Multilingual,
+        size: 3_095_033_483,
+        sha256: "64d182b440b98d5203c4f9bd541544d84c605196c4f7b845dfa11fb23594d1e2",
+        memory_requirement: 4_700 * MB,
+    },
+];
+
+/// Catalog entry of a variant; every variant except custom models has one
+pub fn catalog_model(variant: &ModelVariant) -> Option<&'static CatalogModel> {
+    CATALOG.iter().find(|model| model.variant == *variant)
+}
+
+fn built_in_model(variant: &ModelVariant) -> &'static CatalogModel {
+    catalog_model(variant).expect("every built-in variant has a catalog entry")
+}
+
+pub fn model_filename(variant: &ModelVariant) -> String {
+    match variant {
+        ModelVariant::Custom(id) => custom_model_filename(id),
+        _ => built_in_model(variant).filename.to_string(),
+    }
+}
+
+pub fn model_memory_requirement(variant: &ModelVariant) -> u64 {
+    match variant {
+        // Unregistered custom models cannot be loaded, so the fallback rarely matters
+        ModelVariant::Custom(id) => custom_model(id).map_or(1_000 * MB, |model| model.memory_requirement),
+        _ => built_in_model(variant).memory_requirement,
+    }
+}
+
+pub fn model_languages(variant: &ModelVariant) -> LanguageSupport {
+    match variant {
+        ModelVariant::Custom(id) => custom_model(id).map_or(LanguageSupport::Multilingual, |model| model.languages),
+        _ => built_in_model(variant).languages,
+    }
+}
+
+/// A catalog entry as shown to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvailableModel {
+    pub variant: ModelVariant,
+    pub filename: String,
+    pub mirrors: Vec<String>,
+    pub family: ModelFamily,
+    pub model_size: ModelSize,
+    pub quantization: Quantization,
+    pub languages: LanguageSupport,
+    pub size: u64,
+    pub sha256: String,
+    pub memory_requirement: u64,
+    pub downloaded: bool, // The file is present in the models directory
+}
+
+/// All models in the catalog, smallest size class first
+#[tauri::command]
+pub async fn list_available_models(app_handle: AppHandle) -> Result<Vec<AvailableModel>, String> {
+    let directory = models_directory(&app_handle)?;
+
+    Ok(CATALOG
+        .iter()
+        .map(|model| AvailableModel {
+            variant: model.variant.clone(),
+            filename: model.filename.to_string(),
+            mirrors: model.mirrors(),
+            family: model.family,
+            model_size: model.model_size,
+            quantization: model.quantization,
+            languages: model.languages,
+            size: model.size,
+            sha256: model.sha256.to_string(),
+            memory_requirement: model.memory_requirement,
+            downloaded: directory.join(model.filename).exists(),
+        })
+        .collect())
+}
+
+/// Download a catalog model into the models directory, verified against the
+/// pinned size and SHA-256. Returns the download ID.
+#[tauri::command]
+pub async fn download_model_variant(app_handle: AppHandle, variant: ModelVariant) -> Result<String, String> {
+    let model = catalog_model(&variant)
+        .ok_or_else(|| format!("{:?} is not in the model catalog and cannot be downloaded", variant))?;
+    let target_path = models_directory(&app_handle)?.join(model.filename);
+
+    spawn_download(
+        &app_handle,
+        model.mirrors(),
+        target_path.to_string_lossy().to_string(),
+        model.size,
+        model.sha256.to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every built-in variant. The match has no wildcard, so adding a variant
+    /// fails to compile until it is listed here as well.
+    fn built_in_variants() -> Vec<ModelVariant> {
+        let variants = vec![
+            ModelVariant::Tiny,
+            ModelVariant::TinyEn,
+            ModelVariant::TinyQ5,
+            ModelVariant::Base,
+            ModelVariant::BaseEn,
+            ModelVariant::BaseQ5,
+            ModelVariant::BaseEnQ5,
+            ModelVariant::Small,
+            ModelVariant::SmallEn,
+            ModelVariant::SmallQ5,
+            ModelVariant::Medium,
+            ModelVariant::MediumEn,
+            ModelVariant::MediumQ5,
+            ModelVariant::Large,
+            ModelVariant::LargeQ5,
+            ModelVariant::LargeTurbo,
+            ModelVariant::LargeTurboQ5,
+            ModelVariant::LargeTurboQ8,
+            ModelVariant::DistilLarge,
+        ];
+        for variant in &variants {
+            match variant {
+                ModelVariant::Tiny
+                | ModelVariant::TinyEn
+                | ModelVariant::TinyQ5
+                | ModelVariant::Base
+                | ModelVariant::BaseEn
+                | ModelVariant::BaseQ5
+                | ModelVariant::BaseEnQ5
+                | ModelVariant::Small
+                | ModelVariant::SmallEn
+                | ModelVariant::SmallQ5
+                | ModelVariant::Medium
+                | ModelVariant::MediumEn
+                | ModelVariant::MediumQ5
+                | ModelVariant::Large
+                | ModelVariant::LargeQ5
+                | ModelVariant::LargeTurbo
+                | ModelVariant::LargeTurboQ5
+                | ModelVariant::LargeTurboQ8
+                | ModelVariant::DistilLarge => {}
+                ModelVariant::Custom(_) => unreachable!("custom models are not built in"),
+            }
+        }
+        variants
+    }
+
+    #[test]
+    fn every_built_in_variant_has_one_catalog_entry() {
+        let variants = built_in_variants();
+        for variant in &variants {
+            let entries = CATALOG.iter().filter(|model| model.variant == *variant).count();
+            assert_eq!(entries, 1, "{:?} has {} catalog entries", variant, entries);
+        }
+        assert_eq!(CATALOG.len(), variants.len());
+    }
+
+    #[test]
+    fn catalog_files_are_distinct() {
+        let mut filenames: Vec<&str> = CATALOG.iter().map(|model| model.filename).collect();
+        filenames.sort();
+        filenames.dedup();
+        assert_eq!(filenames.len(), CATALOG.len());
+        assert!(CATALOG.iter().all(|model| model.sha256.len() == 64));
+    }
+}
@@ -0,0 +1,395 @@
+use once_cell::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+use crate::custom_models::{custom_model, read_model_header};
+use crate::file_utils::models_directory;
+use crate::system_info::read_system_memory;
+use crate::whisper::{ModelVariant, WhisperContext};
+
+// Share of total system memory the pool may fill when no budget is configured
+const DEFAULT_BUDGET_FRACTION: f64 = 0.5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelLoadState {
+    Unloaded,
+    Loading,
+    Ready,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhisperModelStatus {
+    pub state: ModelLoadState,
+    pub variant: Option<ModelVariant>, // Model being loaded, or the loaded one once ready
+    pub error: Option<String>,         // Set when loading failed
+}
+
+/// Payload of the `model-load-progress` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelLoadProgress {
+    pub variant: ModelVariant,
+    pub stage: String, // "loading", "warming_up", "ready", "failed" or "evicted"
+    pub progress: f32, // 0.0 to 1.0
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PooledModelInfo {
+    pub variant: ModelVariant,
+    pub estimated_memory: u64, // bytes
+    pub idle_ms: u64,          // Time since the model was last used
+}
+
+/// Payload of `get_model_pool_status`, models ordered most recently used first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelPoolStatus {
+    pub models: Vec<PooledModelInfo>,
+    pub memory_used: u64,   // Sum of the estimates of all loaded models
+    pub memory_budget: u64, // Configured budget, or a share of total system memory
+}
+
+struct PooledModel {
+    context: Arc<WhisperContext>,
+    last_used: Instant,
+}
+
+#[derive(Default)]
+struct ModelPool {
+    models: HashMap<ModelVariant, PooledModel>,
+    statuses: HashMap<ModelVariant, WhisperModelStatus>,
+    // Serializes loads of the same variant so concurrent jobs share one load
+    load_locks: HashMap<ModelVariant, Arc<Mutex<()>>>,
+    // Bumped on unload so a load in flight is discarded when it finishes
+    generations: HashMap<ModelVariant, u64>,
+    last_requested: Option<ModelVariant>,
+    memory_budget: Option<u64>,
+}
+
+impl ModelPool {
+    fn set_status(&mut self, variant: &ModelVariant, state: ModelLoadState, error: Option<String>) {
+        self.statuses.insert(variant.clone(), WhisperModelStatus {
+            state,
+            variant: Some(variant.clone()),
+            error,
+        });
+    }
+
+    fn generation(&self, variant: &ModelVariant) -> u64 {
+        self.generations.get(variant).copied().unwrap_or(0)
+    }
+
+    fn memory_used(&self) -> u64 {
+        self.models.keys().map(|variant| variant.estimated_memory()).sum()
+    }
+
+    fn memory_budget(&self) -> u64 {
+        self.memory_budget.unwrap_or_else(|| {
+            read_system_memory()
+                .map(|memory| (memory.total as f64 * DEFAULT_BUDGET_FRACTION) as u64)
+                .unwrap_or(u64::MAX)
+        })
+    }
+
+    fn least_recently_used(&self, keep: Option<&ModelVariant>) -> Option<ModelVariant> {
+        self.models
+            .iter()
+            .filter(|(variant, _)| Some(*variant) != keep)
+            .min_by_key(|(_, model)| model.last_used)
+            .map(|(variant, _)| variant.clone())
+    }
+
+    /// Evict least recently used models until `incoming` fits both the budget
+    /// and the memory the system currently has available. Returns the evicted
+    /// variants; models still used by a running job are freed when it ends.
+    fn evict_for(&mut self, incoming: Option<&ModelVariant>) -> Vec<ModelVariant> {
+        let required = incoming.map(|variant| variant.estimated_memory()).unwrap_or(0);
+        let budget = self.memory_budget();
+        let available = read_system_memory().map(|memory| memory.available).unwrap_or(u64::MAX);
+
+        let mut evicted = Vec::new();
+        let mut freed = 0u64;
+
+        loop {
+            let used = self.memory_used()
+                - incoming
+                    .filter(|variant| self.models.contains_key(*variant))
+                    .map(|variant| variant.estimated_memory())
+                    .unwrap_or(0);
+
+            let fits_budget = used.saturating_add(required) <= budget;
+            let fits_system = required <= available.saturating_add(freed);
+            if fits_budget && fits_system {
+                break;
+            }
+
+            let Some(victim) = self.least_recently_used(incoming) else {
+                break;
+            };
+
+            freed += victim.estimated_memory();
+            self.models.remove(&victim);
+            self.statuses.remove(&victim);
+            evicted.push(victim);
+        }
+
+        evicted
+    }
+
+    fn status(&self) -> ModelPoolStatus {
+        let mut models: Vec<(&ModelVariant, &PooledModel)> = self.models.iter().collect();
+        models.sort_by(|a, b| b.1.last_used.cmp(&a.1.last_used));
+
+        ModelPoolStatus {
+            models: models
+                .into_iter()
+                .map(|(variant, model)| PooledModelInfo {
+                    variant: variant.clone(),
+                    estimated_memory: variant.estimated_memory(),
+                    idle_ms: model.last_used.elapsed().as_millis() as u64,
+                })
+                .collect(),
+            memory_used: self.memory_used(),
+            memory_budget: self.memory_budget(),
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref MODEL_POOL: Arc<Mutex<ModelPool>> = Arc::new(Mutex::new(ModelPool::default()));
+}
+
+fn emit_load_progress(
+    app_handle: &AppHandle,
+    variant: &ModelVariant,
+    stage: &str,
+    progress: f32,
+    error: Option<String>,
+) {
+    let _ = app_handle.emit("model-load-progress", ModelLoadProgress {
+        variant: variant.clone(),
+        stage: stage.to_string(),
+        progress,
+        error,
+    });
+}
+
+fn emit_evictions(app_handle: &AppHandle, evicted: &[ModelVariant]) {
+    for variant in evicted {
+        emit_load_progress(app_handle, variant, "evicted", 0.0, None);
+    }
+}
+
+/// Look up a loaded model and mark it as used. `None` picks the most
+/// recently used model.
+pub(crate) fn get_model(variant: Option<&ModelVariant>) -> Result<Arc<WhisperContext>, String> {
+    let mut pool = MODEL_POOL.lock().unwrap();
+
+    let variant = match variant {
+        Some(variant) => variant.clone(),
+        None => pool
+            .models
+            .iter()
+            .max_by_key(|(_, model)| model.last_used)
+            .map(|(variant, _)| variant.clone())
+            .ok_or("No Whisper model loaded")?,
+    };
+
+    let model = pool
+        .models
+        .get_mut(&variant)
+        .ok_or_else(|| format!("Whisper model {:?} is not loaded", variant))?;
+    model.last_used = Instant::now();
+
+    Ok(model.context.clone())
+}
+
+fn load_lock_for(variant: &ModelVariant) -> Arc<Mutex<()>> {
+    let mut pool = MODEL_POOL.lock().unwrap();
+    pool.load_locks.entry(variant.clone()).or_default().clone()
+}
+
+/// Load a model into the pool and warm it up, evicting least recently used
+/// models first if needed. Blocks, so call it from a worker thread.
+pub(crate) fn load_model(
+    model_path: PathBuf,
+    variant: &ModelVariant,
+    app_handle: &AppHandle,
+) -> Result<Arc<WhisperContext>, String> {
+    let load_lock = load_lock_for(variant);
+    let _loading = load_lock.lock().unwrap();
+
+    load_model_locked(model_path, variant, app_handle)
+}
+
+/// Return the requested model, loading it from the models directory if it
+/// is not in the pool (e.g. after eviction). Blocks while loading.
+pub(crate) fn acquire_model(
+    variant: &ModelVariant,
+    app_handle: &AppHandle,
+) -> Result<Arc<WhisperContext>, String> {
+    if let Ok(context) = get_model(Some(variant)) {
+        return Ok(context);
+    }
+
+    let load_lock = load_lock_for(variant);
+    let _loading = load_lock.lock().unwrap();
+
+    // Another caller may have loaded it while we waited for the lock
+    if let Ok(context) = get_model(Some(variant)) {
+        return Ok(context);
+    }
+
+    let model_path = models_directory(app_handle)?.join(variant.to_filename());
+    if !model_path.exists() {
+        return Err(format!("Whisper model {:?} is not loaded and has not been downloaded", variant));
+    }
+
+    load_model_locked(model_path, variant, app_handle)
+}
+
+/// Load while holding the variant's load lock
+fn load_model_locked(
+    model_path: PathBuf,
+    variant: &ModelVariant,
+    app_handle: &AppHandle,
+) -> Result<Arc<WhisperContext>, String> {
+    if !model_path.exists() {
+        return Err(format!("Model file not found: {:?}", model_path));
+    }
+    // Imported files are checked before whisper.cpp sees them
+    if let ModelVariant::Custom(id) = variant {
+        if custom_model(id).is_none() {
+            return Err(format!("Unknown custom model: {}", id));
+        }
+        read_model_header(&model_path)?;
+    }
+
+    let (generation, evicted) = {
+        let mut pool = MODEL_POOL.lock().unwrap();
+        pool.last_requested = Some(variant.clone());
+        pool.set_status(variant, ModelLoadState::Loading, None);
+        let evicted = pool.evict_for(Some(variant));
+        (pool.generation(variant), evicted)
+    };
+    emit_evictions(app_handle, &evicted);
+    emit_load_progress(app_handle, variant, "loading", 0.0, None);
+
+    let outcome = WhisperContext::new(model_path, variant.clone()).and_then(|context| {
+        emit_load_progress(app_handle, variant, "warming_up", 0.8, None);
+        context.warm_up()?;
+        Ok(context)
+    });
+
+    let mut pool = MODEL_POOL.lock().unwrap();
+    if pool.generation(variant) != generation {
+        return Err(format!("Loading {:?} was cancelled by an unload request", variant));
+    }
+
+    match outcome {
+        Ok(context) => {
+            let context = Arc::new(context);
+            pool.models.insert(variant.clone(), PooledModel {
+                context: context.clone(),
+                last_used: Instant::now(),
+            });
+            pool.set_status(variant, ModelLoadState::Ready, None);
+            emit_load_progress(app_handle, variant, "ready", 1.0, None);
+            Ok(context)
+        }
+        Err(e) => {
+            pool.set_status(variant, ModelLoadState::Failed, Some(e.clone()));
+            emit_load_progress(app_handle, variant, "failed", 1.0, Some(e.clone()));
+            Err(e)
+        }
+    }
+}
+
+/// Load a model into the pool on a blocking worker and warm it up. Other
+/// loaded models keep serving transcriptions meanwhile; least recently used
+/// ones are evicted if the memory budget requires it.
+/// Progress is reported through `model-load-progress` events.
+#[tauri::command]
+pub async fn load_whisper_model(
+    path: String,
+    variant: ModelVariant,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let model_path = PathBuf::from(path);
+
+    if !model_path.exists() {
+        return Err(format!("Model file not found: {:?}", model_path));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        load_model(model_path, &variant, &app_handle).map(|_| ())
+    })
+    .await
+    .map_err(|e| format!("Model loading task failed: {}", e))?
+}
+
+/// Unload one variant, or every loaded model when no variant is given
+#[tauri::command]
+pub async fn unload_whisper_model(variant: Option<ModelVariant>) -> Result<(), String> {
+    let mut pool = MODEL_POOL.lock().unwrap();
+
+    let variants: Vec<ModelVariant> = match variant {
+        Some(variant) => vec![variant],
+        None => pool.statuses.keys().cloned().collect(),
+    };
+
+    for variant in variants {
+        *pool.generations.entry(variant.clone()).or_insert(0) += 1;
+        pool.models.remove(&variant);
+        pool.statuses.remove(&variant);
+    }
+
+    Ok(())
+}
+
+/// Status of one variant, or of the most recently requested one when no
+/// variant is given
+#[tauri::command]
+pub async fn get_whisper_model_status(
+    variant: Option<ModelVariant>,
+) -> Result<WhisperModelStatus, String> {
+    let pool = MODEL_POOL.lock().unwrap();
+
+    let status = variant
+        .or_else(|| pool.last_requested.clone())
+        .and_then(|variant| pool.statuses.get(&variant).cloned());
+
+    Ok(status.unwrap_or(WhisperModelStatus {
+        state: ModelLoadState::Unloaded,
+        variant: None,
+        error: None,
+    }))
+}
+
+#[tauri::command]
+pub async fn get_model_pool_status() -> Result<ModelPoolStatus, String> {
+    Ok(MODEL_POOL.lock().unwrap().status())
+}
+
+/// Set the pool's memory budget in bytes (`None` restores the default share of
+/// system memory) and evict least recently used models that no longer fit
+#[tauri::command]
+pub async fn set_model_memory_budget(
+    budget: Option<u64>,
+    app_handle: AppHandle,
+) -> Result<ModelPoolStatus, String> {
+    let (evicted, status) = {
+        let mut pool = MODEL_POOL.lock().unwrap();
+        pool.memory_budget = budget;
+        let evicted = pool.evict_for(None);
+        (evicted, pool.status())
+    };
+    emit_evictions(&app_handle, &evicted);
+
+    Ok(status)
+}
@@ -0,0 +1,389 @@
+use arboard::Clipboard;
+use enigo::{Direction, Enigo, Key, Keyboard, Settings as EnigoSettings};
+use once_cell::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::settings_store::{load_settings, save_settings};
+
+// Time for the target application to read the clipboard before it is restored
+const DEFAULT_RESTORE_DELAY_MS: u64 = 300;
+const MAX_RESTORE_DELAY_MS: u64 = 5_000;
+
+const SETTINGS_FILE: &str = "output.json";
+
+/// Where transcribed text goes after a hotkey recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    #[default]
+    Clipboard, // Only place the text on the clipboard
+    Paste,     // Place it on the clipboard and synthesize the paste shortcut
+    Type,      // Place it on the clipboard and type it key by key
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputSettings {
+    pub mode: OutputMode,
+    pub restore_clipboard: bool, // Keep the previous clipboard text after pasting or typing
+    pub restore_delay_ms: u64,
+}
+
+impl Default for OutputSettings {
+    fn default() -> Self {
+        OutputSettings {
+            mode: OutputMode::default(),
+            restore_clipboard: true,
+            restore_delay_ms: DEFAULT_RESTORE_DELAY_MS,
+        }
+    }
+}
+
+impl OutputSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.restore_delay_ms > MAX_RESTORE_DELAY_MS {
+            return Err(format!("Clipboard restore delay must be at most {} ms", MAX_RESTORE_DELAY_MS));
+        }
+        Ok(())
+    }
+}
+
+/// What `deliver_text` actually did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputDelivery {
+    pub mode: OutputMode,         // Mode used; falls back to clipboard when our window has focus
+    pub clipboard_restored: bool, // A previous clipboard text was put back
+}
+
+/// Synthesizes input into whichever window has keyboard focus
+pub trait InputSynthesizer {
+    fn paste(&mut self) -> Result<(), String>;
+    fn type_text(&mut self, text: &str) -> Result<(), String>;
+}
+
+/// Text access to the clipboard
+pub trait TextClipboard {
+    fn get_text(&mut self) -> Result<String, String>;
+    fn set_text(&mut self, text: &str) -> Result<(), String>;
+}
+
+/// Input synthesis through enigo: SendInput on Windows, CGEvent on macOS,
+/// XTest on X11 (works under Xvfb) and the virtual keyboard protocol on Wayland.
+/// Connects on first use, so clipboard-only delivery works without a display.
+#[derive(Default)]
+pub struct EnigoSynthesizer {
+    enigo: Option<Enigo>,
+}
+
+impl EnigoSynthesizer {
+    fn enigo(&mut self) -> Result<&mut Enigo, String> {
+        if self.enigo.is_none() {
+            let enigo = Enigo::new(&EnigoSettings::default())
+                .map_err(|e| format!("Failed to initialize input synthesis: {}", e))?;
+            self.enigo = Some(enigo);
+        }
+        Ok(self.enigo.as_mut().unwrap())
+    }
+}
+
+impl InputSynthesizer for EnigoSynthesizer {
+    fn paste(&mut self) -> Result<(), String> {
+        let enigo = self.enigo()?;
+        let modifier = if cfg!(target_os = "macos") { Key::Meta } else { Key::Control };
+        let result = enigo
+            .key(modifier, Direction::Press)
+            .and_then(|_| enigo.key(Key::Unicode('v'), Direction::Click));
+        // Always release the modifier, even if the click failed
+        let release = enigo.key(modifier, Direction::Release);
+
+        result
+            .and(release)
+            .map_err(|e| format!("Failed to synthesize paste: {}", e))
+    }
+
+    fn type_text(&mut self, text: &str) -> Result<(), String> {
+        self.enigo()?
+            .text(text)
+            .map_err(|e| format!("Failed to type text: {}", e))
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref OUTPUT_SETTINGS: Mutex<OutputSettings> = Mutex::new(OutputSettings::default());
+    // Kept alive for the whole session: on X11 and Wayland the clipboard
+    // contents are served by the process that set them
+    static ref CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+}
+
+fn with_clipboard<T>(f: impl FnOnce(&mut Clipboard) -> Result<T, arboard::Error>) -> Result<T, String> {
+    let mut clipboard = CLIPBOARD.lock().unwrap();
+    if clipboard.is_none() {
+        *clipboard = Some(Clipboard::new().map_err(|e| format!("Failed to open clipboard: {}", e))?);
+    }
+    f(clipboard.as_mut().unwrap()).map_err(|e| format!("Clipboard error: {}", e))
+}
+
+/// The system clipboard, shared for the whole session
+struct SystemClipboard;
+
+impl TextClipboard for SystemClipboard {
+    fn get_text(&mut self) -> Result<String, String> {
+        with_clipboard(|clipboard| clipboard.get_text())
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        with_clipboard(|clipboard| clipboard.set_text(text))
+    }
+}
+
+/// Synthesized input would land in our own window while it has focus
+fn app_has_focus(app_handle: &AppHandle) -> bool {
+    app_handle
+        .get_webview_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false)
+}
+
+/// Put `text` on the clipboard and, depending on `mode`, paste or type it into
+/// the focused application. Blocks for the restore delay when the clipboard is restored.
+pub fn deliver_text(
+    app_handle: &AppHandle,
+    text: &str,
+    mode: Option<OutputMode>,
+    synthesizer: &mut dyn InputSynthesizer,
+) -> Result<OutputDelivery, String> {
+    let settings = OUTPUT_SETTINGS.lock().unwrap().clone();
+    let mut mode = mode.unwrap_or(settings.mode);
+    if mode != OutputMode::Clipboard && app_has_focus(app_handle) {
+        mode = OutputMode::Clipboard;
+    }
+
+    deliver_with(text, mode, &settings, &mut SystemClipboard, synthesizer)
+}
+
+fn deliver_with(
+    text: &str,
+    mode: OutputMode,
+    settings: &OutputSettings,
+    clipboard: &mut dyn TextClipboard,
+    synthesizer: &mut dyn InputSynthesizer,
+) -> Result<OutputDelivery, String> {
+    // Only text can be restored; other clipboard contents are left replaced
+    let previous = if mode != OutputMode::Clipboard && settings.restore_clipboard {
+        clipboard.get_text().ok()
+    } else {
+        None
+    };
+
+    clipboard.set_text(text)?;
+
+    let synthesized = match mode {
+        OutputMode::Clipboard => Ok(()),
+        OutputMode::Paste => synthesizer.paste(),
+        OutputMode::Type => synthesizer.type_text(text),
+    };
+
+    // Restore even when synthesis failed, the text is not needed on the clipboard then
+    let clipboard_restored = match previous {
+        Some(previous) => {
+            if synthesized.is_ok() {
+                std::thread::sleep(Duration::from_millis(settings.restore_delay_ms));
+            }
+            clipboard.set_text(&previous).is_ok()
+        }
+        None => false,
+    };
+
+    synthesized?;
+    Ok(OutputDelivery { mode, clipboard_restored })
+}
+
+/// Load the stored output settings. Call during setup.
+pub fn init_output_settings(app_handle: &AppHandle) {
+    match load_settings(app_handle, SETTINGS_FILE, OutputSettings::validate) {
+        Ok(Some(settings)) => *OUTPUT_SETTINGS.lock().unwrap() = settings,
+        Ok(None) => {}
+        Err(e) => eprintln!("Ignoring stored output settings: {}", e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_output_settings() -> Result<OutputSettings, String> {
+    Ok(OUTPUT_SETTINGS.lock().unwrap().clone())
+}
+
+#[tauri::command]
+pub async fn set_output_settings(settings: OutputSettings, app_handle: AppHandle) -> Result<(), String> {
+    settings.validate()?;
+    save_settings(&app_handle, SETTINGS_FILE, &settings)?;
+    *OUTPUT_SETTINGS.lock().unwrap() = settings;
+    Ok(())
+}
+
+/// Send text to the focused application, using the configured mode unless one is given
+#[tauri::command]
+pub async fn send_text_to_focused_app(
+    text: String,
+    mode: Option<OutputMode>,
+    app_handle: AppHandle,
+) -> Result<OutputDelivery, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        deliver_text(&app_handle, &text, mode, &mut EnigoSynthesizer::default())
+    })
+        .await
+        .map_err(|e| format!("Output task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeClipboard {
+        text: Option<String>,
+        writes: Vec<String>,
+    }
+
+    impl TextClipboard for FakeClipboard {
+        fn get_text(&mut self) -> Result<String, String> {
+            self.text.clone().ok_or_else(|| "Clipboard is empty".to_string())
+        }
+
+        fn set_text(&mut self, text: &str) -> Result<(), String> {
+            self.text = Some(text.to_string());
+            self.writes.push(text.to_string());
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingSynthesizer {
+        calls: Vec<String>,
+        fail: bool,
+    }
+
+    impl InputSynthesizer for RecordingSynthesizer {
+        fn paste(&mut self) -> Result<(), String> {
+            self.calls.push("paste".to_string());
+            if self.fail {
+                return Err("Failed to synthesize paste".to_string());
+            }
+            Ok(())
+        }
+
+        fn type_text(&mut self, text: &str) -> Result<(), String> {
+            self.calls.push(format!("type {}", text));
+            Ok(())
+        }
+    }
+
+    fn settings(restore_clipboard: bool) -> OutputSettings {
+        OutputSettings {
+            mode: OutputMode::Clipboard,
+            restore_clipboard,
+            restore_delay_ms: 0,
+        }
+    }
+
+    fn clipboard_with(text: &str) -> FakeClipboard {
+        FakeClipboard {
+            text: Some(text.to_string()),
+            writes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn clipboard_mode_only_sets_clipboard() {
+        let mut clipboard = clipboard_with("previous");
+        let mut synthesizer = RecordingSynthesizer::default();
+
+        let delivery = deliver_with("hello", OutputMode::Clipboard, &settings(true), &mut clipboard, &mut synthesizer).unwrap();
+
+        assert_eq!(delivery.mode, OutputMode::Clipboard);
+        assert!(!delivery.clipboard_restored);
+        assert_eq!(clipboard.text.as_deref(), Some("hello"));
+        assert!(synthesizer.calls.is_empty());
+    }
+
+    #[test]
+    fn paste_restores_previous_clipboard() {
+        let mut clipboard = clipboard_with("previous");
+        let mut synthesizer = RecordingSynthesizer::default();
+
+        let delivery = deliver_with("hello", OutputMode::Paste, &settings(true), &mut clipboard, &mut synthesizer).unwrap();
+
+        assert!(delivery.clipboard_restored);
+        assert_eq!(clipboard.writes, vec!["hello", "previous"]);
+        assert_eq!(synthesizer.calls, vec!["paste"]);
+    }
+
+    #[test]
+    fn failed_paste_still_restores_clipboard() {
+        let mut clipboard = clipboard_with("previous");
+        let mut synthesizer = RecordingSynthesizer {
+            fail: true,
+            ..Default::default()
+        };
+
+        let result = deliver_with("hello", OutputMode::Paste, &settings(true), &mut clipboard, &mut synthesizer);
+
+        assert!(result.is_err());
+        assert_eq!(clipboard.text.as_deref(), Some("previous"));
+    }
+
+    #[test]
+    fn paste_without_restore_keeps_text_on_clipboard() {
+        let mut clipboard = clipboard_with("previous");
+        let mut synthesizer = RecordingSynthesizer::default();
+
+        let delivery = deliver_with("hello", OutputMode::Paste, &settings(false), &mut clipboard, &mut synthesizer).unwrap();
+
+        assert!(!delivery.clipboard_restored);
+        assert_eq!(clipboard.writes, vec!["hello"]);
+    }
+
+    #[test]
+    fn type_places_text_on_clipboard_and_restores() {
+        let mut clipboard = clipboard_with("previous");
+        let mut synthesizer = RecordingSynthesizer::default();
+
+        let delivery = deliver_with("hello", OutputMode::Type, &settings(true), &mut clipboard, &mut synthesizer).unwrap();
+
+        assert!(delivery.clipboard_restored);
+        assert_eq!(clipboard.writes, vec!["hello", "previous"]);
+        assert_eq!(synthesizer.calls, vec!["type hello"]);
+    }
+
+    #[test]
+    fn nothing_restored_from_empty_clipboard() {
+        let mut clipboard = FakeClipboard::default();
+        let mut synthesizer = RecordingSynthesizer::default();
+
+        let delivery = deliver_with("hello", OutputMode::Type, &settings(true), &mut clipboard, &mut synthesizer).unwrap();
+
+        assert!(!delivery.clipboard_restored);
+        assert_eq!(clipboard.text.as_deref(), Some("hello"));
+    }
+
+    // Drives arboard and enigo against a real display. Run under a virtual one with
+    //   xvfb-run -a cargo test --lib output_sink -- --ignored
+    #[test]
+    #[ignore = "needs an X11 or Wayland display, e.g. xvfb-run"]
+    fn system_backends_on_display() {
+        if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+            panic!("No display; run this test under xvfb-run");
+        }
+        let mut synthesizer = EnigoSynthesizer::default();
+
+        SystemClipboard.set_text("previous").unwrap();
+        let delivery = deliver_with("hello", OutputMode::Paste, &settings(true), &mut SystemClipboard, &mut synthesizer).unwrap();
+        assert!(delivery.clipboard_restored);
+        assert_eq!(SystemClipboard.get_text().unwrap(), "previous");
+
+        let delivery = deliver_with("typed", OutputMode::Type, &settings(false), &mut SystemClipboard, &mut synthesizer).unwrap();
+        assert!(!delivery.clipboard_restored);
+        assert_eq!(SystemClipboard.get_text().unwrap(), "typed");
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+fn settings_path(app_handle: &AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    let dir = app_handle
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get config directory: {}", e))?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    Ok(dir.join(file_name))
+}
+
+/// Read and validate settings stored in the app config directory, next to
+/// `shortcuts.json`. Returns None when nothing has been stored yet.
+pub(crate) fn load_settings<T: DeserializeOwned>(
+    app_handle: &AppHandle,
+    file_name: &str,
+    validate: impl FnOnce(&T) -> Result<(), String>,
+) -> Result<Option<T>, String> {
+    let path = settings_path(app_handle, file_name)?;
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    let settings: T = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid settings in {}: {}", path.display(), e))?;
+    validate(&settings).map_err(|e| format!("Invalid settings in {}: {}", path.display(), e))?;
+
+    Ok(Some(settings))
+}
+
+/// Write settings to the app config directory. The file is replaced
+/// atomically so a crash never leaves it half written.
+pub(crate) fn save_settings<T: Serialize>(app_handle: &AppHandle, file_name: &str, settings: &T) -> Result<(), String> {
+    let path = settings_path(app_handle, file_name)?;
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, content).map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
+    std::fs::rename(&temp_path, &path).map_err(|e| format!("Failed to write {}: {}", file_name, e))
+}
@@ -0,0 +1,333 @@
+use once_cell::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::dictation::{cancel_dictation_hotkey, handle_dictation_hotkey, last_transcript, HotkeyMode};
+use crate::output_sink::{deliver_text, EnigoSynthesizer, OutputMode};
+use crate::settings_store::{load_settings, save_settings};
+
+const CONFIG_FILE: &str = "shortcuts.json";
+
+/// Something a global shortcut can trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    ToggleRecording,
+    PushToTalk,
+    Cancel,
+    PasteLastTranscript,
+    ShowWindow,
+}
+
+impl ShortcutAction {
+    const ALL: [ShortcutAction; 5] = [
+        ShortcutAction::ToggleRecording,
+        ShortcutAction::PushToTalk,
+        ShortcutAction::Cancel,
+        ShortcutAction::PasteLastTranscript,
+        ShortcutAction::ShowWindow,
+    ];
+
+    fn default_accelerator(&self) -> Option<&'static str> {
+        match self {
+            ShortcutAction::ToggleRecording => Some("CommandOrControl+Shift+Space"),
+            _ => None,
+        }
+    }
+}
+
+/// An action and the accelerator bound to it, as stored in `shortcuts.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortcutBinding {
+    pub action: ShortcutAction,
+    pub accelerator: Option<String>, // e.g. "CommandOrControl+Shift+Space"; None when unbound
+    #[serde(default)]
+    pub registered: bool,            // Whether the OS accepted the registration
+}
+
+/// Why a shortcut could not be bound, tagged by `kind` for the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShortcutError {
+    /// The accelerator string could not be parsed
+    InvalidAccelerator { accelerator: String, message: String },
+    /// Another action of this app already uses the accelerator
+    Conflict { accelerator: String, action: ShortcutAction },
+    /// The OS refused the registration, usually because another application owns it
+    Unavailable { accelerator: String, message: String },
+    /// The action has no accelerator to unregister or rebind
+    NotBound { action: ShortcutAction },
+    /// Reading or writing the config file failed
+    Config { message: String },
+}
+
+impl std::fmt::Display for ShortcutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShortcutError::InvalidAccelerator { accelerator, message } => {
+                write!(f, "Invalid shortcut {}: {}", accelerator, message)
+            }
+            ShortcutError::Conflict { accelerator, action } => {
+                write!(f, "Shortcut {} is already bound to {:?}", accelerator, action)
+            }
+            ShortcutError::Unavailable { accelerator, message } => {
+                write!(f, "Shortcut {} could not be registered: {}", accelerator, message)
+            }
+            ShortcutError::NotBound { action } => write!(f, "No shortcut is bound to {:?}", action),
+            ShortcutError::Config { message } => write!(f, "Shortcut config error: {}", message),
+        }
+    }
+}
+
+/// Payload of the `shortcut-triggered` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortcutTriggered {
+    pub action: ShortcutAction,
+    pub pressed: bool, // false for the release of a push-to-talk key
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ShortcutConfig {
+    bindings: Vec<ShortcutBinding>,
+}
+
+lazy_static::lazy_static! {
+    static ref BINDINGS: Mutex<Vec<ShortcutBinding>> = Mutex::new(Vec::new());
+}
+
+/// Bindings from the config file, with every action present exactly once.
+/// Falls back to the defaults when the file is missing or unreadable.
+fn load_bindings(app_handle: &AppHandle) -> Vec<ShortcutBinding> {
+    let stored: Vec<ShortcutBinding> = match load_settings::<ShortcutConfig>(app_handle, CONFIG_FILE, |_| Ok(())) {
+        Ok(config) => config.map(|config| config.bindings).unwrap_or_default(),
+        Err(e) => {
+            eprintln!("Ignoring stored shortcuts: {}", e);
+            Vec::new()
+        }
+    };
+
+    ShortcutAction::ALL
+        .iter()
+        .map(|action| {
+            let accelerator = match stored.iter().find(|binding| binding.action == *action) {
+                Some(binding) => binding.accelerator.clone(),
+                None => action.default_accelerator().map(str::to_string),
+            };
+            ShortcutBinding {
+                action: *action,
+                accelerator,
+                registered: false,
+            }
+        })
+        .collect()
+}
+
+fn save_bindings(app_handle: &AppHandle, bindings: &[ShortcutBinding]) -> Result<(), ShortcutError> {
+    let config = ShortcutConfig {
+        bindings: bindings.to_vec(),
+    };
+    save_settings(app_handle, CONFIG_FILE, &config).map_err(|message| ShortcutError::Config { message })
+}
+
+fn parse_accelerator(accelerator: &str) -> Result<Shortcut, ShortcutError> {
+    Shortcut::from_str(accelerator).map_err(|e| ShortcutError::InvalidAccelerator {
+        accelerator: accelerator.to_string(),
+        message: e.to_string(),
+    })
+}
+
+fn bound_shortcut(binding: &ShortcutBinding) -> Option<Shortcut> {
+    binding
+        .accelerator
+        .as_deref()
+        .and_then(|accelerator| Shortcut::from_str(accelerator).ok())
+}
+
+/// Run a shortcut's action. Recording actions go through the dictation worker
+/// so the press and release of a push-to-talk key are handled in order.
+fn dispatch(app_handle: &AppHandle, action: ShortcutAction, state: ShortcutState) {
+    let pressed = state == ShortcutState::Pressed;
+    if !pressed && action != ShortcutAction::PushToTalk {
+        return;
+    }
+    let _ = app_handle.emit("shortcut-triggered", ShortcutTriggered { action, pressed });
+
+    match action {
+        ShortcutAction::ToggleRecording => handle_dictation_hotkey(app_handle, HotkeyMode::Toggle, state),
+        ShortcutAction::PushToTalk => handle_dictation_hotkey(app_handle, HotkeyMode::PushToTalk, state),
+        ShortcutAction::Cancel => cancel_dictation_hotkey(app_handle),
+        ShortcutAction::PasteLastTranscript => {
+            if let Some(text) = last_transcript() {
+                let app = app_handle.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    if let Err(e) = deliver_text(&app, &text, Some(OutputMode::Paste), &mut EnigoSynthesizer::default()) {
+                        eprintln!("Failed to paste last transcript: {}", e);
+                    }
+                });
+            }
+        }
+        ShortcutAction::ShowWindow => {
+            if let Some(window) = app_handle.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+    }
+}
+
+fn register_with_os(app_handle: &AppHandle, action: ShortcutAction, accelerator: &str) -> Result<(), ShortcutError> {
+    let shortcut = parse_accelerator(accelerator)?;
+    app_handle
+        .global_shortcut()
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            dispatch(app, action, event.state());
+        })
+        .map_err(|e| ShortcutError::Unavailable {
+            accelerator: accelerator.to_string(),
+            message: e.to_string(),
+        })
+}
+
+fn unregister_from_os(app_handle: &AppHandle, binding: &ShortcutBinding) {
+    if !binding.registered {
+        return;
+    }
+    if let Some(shortcut) = bound_shortcut(binding) {
+        let _ = app_handle.global_shortcut().unregister(shortcut);
+    }
+}
+
+/// Fail if `accelerator` is already used by an action other than `action`
+fn check_conflict(
+    bindings: &[ShortcutBinding],
+    action: ShortcutAction,
+    accelerator: &str,
+) -> Result<(), ShortcutError> {
+    let shortcut = parse_accelerator(accelerator)?;
+    let taken_by = bindings.iter().find(|binding| {
+        binding.action != action && bound_shortcut(binding).map(|bound| bound.id()) == Some(shortcut.id())
+    });
+
+    match taken_by {
+        Some(binding) => Err(ShortcutError::Conflict {
+            accelerator: accelerator.to_string(),
+            action: binding.action,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Bind `accelerator` to `action`, replacing its previous accelerator. The
+/// previous registration is restored if the new one is refused.
+fn bind(app_handle: &AppHandle, action: ShortcutAction, accelerator: &str) -> Result<ShortcutBinding, ShortcutError> {
+    let mut bindings = BINDINGS.lock().unwrap();
+    check_conflict(&bindings, action, accelerator)?;
+
+    let index = bindings
+        .iter()
+        .position(|binding| binding.action == action)
+        .ok_or(ShortcutError::NotBound { action })?;
+    let previous = bindings[index].clone();
+
+    unregister_from_os(app_handle, &previous);
+    if let Err(e) = register_with_os(app_handle, action, accelerator) {
+        if let Some(accelerator) = previous.accelerator.as_deref().filter(|_| previous.registered) {
+            let _ = register_with_os(app_handle, action, accelerator);
+        }
+        return Err(e);
+    }
+
+    bindings[index] = ShortcutBinding {
+        action,
+        accelerator: Some(accelerator.to_string()),
+        registered: true,
+    };
+    save_bindings(app_handle, &bindings)?;
+    let _ = app_handle.emit("shortcuts-changed", bindings.clone());
+
+    Ok(bindings[index].clone())
+}
+
+/// Load the shortcut config and register every bound action. Failures are
+/// logged and leave the binding in place but unregistered.
+pub fn setup_shortcuts(app_handle: &AppHandle) {
+    let mut bindings = load_bindings(app_handle);
+
+    for binding in bindings.iter_mut() {
+        let Some(accelerator) = binding.accelerator.clone() else {
+            continue;
+        };
+        match register_with_os(app_handle, binding.action, &accelerator) {
+            Ok(()) => binding.registered = true,
+            Err(e) => eprintln!("Failed to register shortcut for {:?}: {}", binding.action, e),
+        }
+    }
+
+    *BINDINGS.lock().unwrap() = bindings;
+}
+
+#[tauri::command]
+pub async fn list_shortcuts() -> Result<Vec<ShortcutBinding>, ShortcutError> {
+    Ok(BINDINGS.lock().unwrap().clone())
+}
+
+/// Bind an accelerator to an action that has none yet
+#[tauri::command]
+pub async fn register_shortcut(
+    action: ShortcutAction,
+    accelerator: String,
+    app_handle: AppHandle,
+) -> Result<ShortcutBinding, ShortcutError> {
+    let bound = BINDINGS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|binding| binding.action == action)
+        .and_then(|binding| binding.accelerator.clone());
+    if let Some(existing) = bound {
+        if parse_accelerator(&existing)?.id() != parse_accelerator(&accelerator)?.id() {
+            return Err(ShortcutError::Conflict { accelerator: existing, action });
+        }
+    }
+
+    bind(&app_handle, action, &accelerator)
+}
+
+/// Replace the accelerator of an already bound action
+#[tauri::command]
+pub async fn rebind_shortcut(
+    action: ShortcutAction,
+    accelerator: String,
+    app_handle: AppHandle,
+) -> Result<ShortcutBinding, ShortcutError> {
+    let bound = BINDINGS
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|binding| binding.action == action && binding.accelerator.is_some());
+    if !bound {
+        return Err(ShortcutError::NotBound { action });
+    }
+
+    bind(&app_handle, action, &accelerator)
+}
+
+/// Remove an action's accelerator and release it with the OS
+#[tauri::command]
+pub async fn unregister_shortcut(action: ShortcutAction, app_handle: AppHandle) -> Result<(), ShortcutError> {
+    let mut bindings = BINDINGS.lock().unwrap();
+    let binding = bindings
+        .iter_mut()
+        .find(|binding| binding.action == action && binding.accelerator.is_some())
+        .ok_or(ShortcutError::NotBound { action })?;
+
+    unregister_from_os(&app_handle, binding);
+    binding.accelerator = None;
+    binding.registered = false;
+
+    save_bindings(&app_handle, &bindings)?;
+    let _ = app_handle.emit("shortcuts-changed", bindings.clone());
+    Ok(())
+}
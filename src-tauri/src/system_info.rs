@@ -13,6 +13,11 @@ pub struct SystemMemory {
 /// Returns memory statistics in bytes
 #[command]
 pub async fn get_system_memory() -> Result<SystemMemory, String> {
+    read_system_memory()
+}
+
+/// Synchronous variant of `get_system_memory` for use from backend code
+pub fn read_system_memory() -> Result<SystemMemory, String> {
     #[cfg(target_os = "windows")]
     {
         get_memory_windows()
@@ -0,0 +1,430 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+use crate::history::{load_transcript, with_database};
+use crate::whisper::{TranscriptionResult, TranscriptionSegment};
+
+// Common broadcast subtitle limits
+const DEFAULT_MAX_LINE_LENGTH: usize = 42;
+const DEFAULT_MAX_LINES: usize = 2;
+const DEFAULT_MAX_CUE_DURATION_MS: i64 = 7_000;
+
+/// Output format of `export_transcript`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Srt,
+    Vtt,
+    Txt,
+    Json,
+    #[serde(rename = "md")]
+    Markdown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    pub max_line_length: usize,   // Subtitle line length in characters
+    pub max_lines: usize,         // Lines per subtitle cue
+    pub max_cue_duration_ms: i64, // Longer cues are split at word boundaries
+    pub include_timestamps: bool, // Prefix each segment with its start time in TXT and Markdown
+    pub title: Option<String>,    // Markdown heading
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
+            max_lines: DEFAULT_MAX_LINES,
+            max_cue_duration_ms: DEFAULT_MAX_CUE_DURATION_MS,
+            include_timestamps: false,
+            title: None,
+        }
+    }
+}
+
+impl ExportOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(10..=200).contains(&self.max_line_length) {
+            return Err("Subtitle line length must be between 10 and 200 characters".to_string());
+        }
+        if !(1..=5).contains(&self.max_lines) {
+            return Err("Subtitle cues must have between 1 and 5 lines".to_string());
+        }
+        if self.max_cue_duration_ms < 500 {
+            return Err("Maximum subtitle duration must be at least 500 ms".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// What `export_transcript` wrote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub bytes: u64,
+    pub cues: usize, // Subtitle cues, or segments for the other formats
+}
+
+/// One subtitle shown on screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleCue {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub lines: Vec<String>,
+}
+
+/// Greedy word wrap; words longer than a line get a line of their own
+fn wrap_words(text: &str, max_line_length: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let needed = line.chars().count() + word.chars().count() + usize::from(!line.is_empty());
+        if !line.is_empty() && needed > max_line_length {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Break segments into subtitle cues of at most `max_lines` lines. A segment
+/// too long for one cue is split, with its time shared out by character
+/// count. A cue also ends early when it would stay on screen longer than
+/// `max_cue_duration_ms`, so the cues always cover the whole segment; only a
+/// single word can stay on screen longer.
+pub fn build_cues(segments: &[TranscriptionSegment], options: &ExportOptions) -> Vec<SubtitleCue> {
+    let mut cues = Vec::new();
+
+    for segment in segments {
+        let words: Vec<&str> = segment.text.split_whitespace().collect();
+        let total_chars: usize = words.iter().map(|word| word.chars().count()).sum();
+        if total_chars == 0 {
+            continue;
+        }
+
+        let duration = (segment.t1 - segment.t0).max(0);
+        // Characters a cue may hold before it is on screen too long
+        let max_cue_chars = match duration {
+            0 => usize::MAX,
+            duration => (options.max_cue_duration_ms * total_chars as i64 / duration) as usize,
+        };
+
+        let mut groups: Vec<(Vec<&str>, usize)> = Vec::new();
+        for word in words {
+            let word_chars = word.chars().count();
+            let fits = groups.last().is_some_and(|(group, chars)| {
+                chars + word_chars <= max_cue_chars
+                    && wrap_words(&format!("{} {}", group.join(" "), word), options.max_line_length).len()
+                        <= options.max_lines
+            });
+            match groups.last_mut() {
+                Some((group, chars)) if fits => {
+                    group.push(word);
+                    *chars += word_chars;
+                }
+                _ => groups.push((vec![word], word_chars)),
+            }
+        }
+
+        let mut chars_before = 0;
+        for (group, chars) in groups {
+            let start_ms = segment.t0 + duration * chars_before as i64 / total_chars as i64;
+            chars_before += chars;
+            let end_ms = segment.t0 + duration * chars_before as i64 / total_chars as i64;
+
+            cues.push(SubtitleCue {
+                start_ms,
+                end_ms,
+                lines: wrap_words(&group.join(" "), options.max_line_length),
+            });
+        }
+    }
+
+    cues
+}
+
+/// `HH:MM:SS` followed by `separator` and milliseconds
+fn format_timestamp(ms: i64, separator: char) -> String {
+    let ms = ms.max(0);
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// `[HH:MM:SS]` marker used in plain text and Markdown
+fn format_marker(ms: i64) -> String {
+    let seconds = ms.max(0) / 1000;
+    format!("[{:02}:{:02}:{:02}]", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+fn cue_text(cue: &SubtitleCue) -> String {
+    // Players would read "-->" as a timing line
+    cue.lines.join("\n").replace("-->", "->")
+}
+
+fn render_srt(cues: &[SubtitleCue]) -> String {
+    let mut output = String::new();
+    for (index, cue) in cues.iter().enumerate() {
+        let _ = writeln!(output, "{}", index + 1);
+        let _ = writeln!(
+            output,
+            "{} --> {}",
+            format_timestamp(cue.start_ms, ','),
+            format_timestamp(cue.end_ms, ',')
+        );
+        let _ = writeln!(output, "{}\n", cue_text(cue));
+    }
+    output
+}
+
+fn render_vtt(cues: &[SubtitleCue]) -> String {
+    let mut output = String::from("WEBVTT\n\n");
+    for cue in cues {
+        let _ = writeln!(
+            output,
+            "{} --> {}",
+            format_timestamp(cue.start_ms, '.'),
+            format_timestamp(cue.end_ms, '.')
+        );
+        let _ = writeln!(output, "{}\n", cue_text(cue));
+    }
+    output
+}
+
+fn render_txt(result: &TranscriptionResult, options: &ExportOptions) -> String {
+    if !options.include_timestamps {
+        return format!("{}\n", result.text.trim());
+    }
+
+    let mut output = String::new();
+    for segment in result.segments.iter().filter(|segment| !segment.text.trim().is_empty()) {
+        let _ = writeln!(output, "{} {}", format_marker(segment.t0), segment.text.trim());
+    }
+    output
+}
+
+fn render_markdown(result: &TranscriptionResult, options: &ExportOptions) -> String {
+    let title = options.title.as_deref().unwrap_or("Transcript");
+    let mut output = format!("# {}\n\n*Language: {}*\n\n", title, result.language);
+
+    if options.include_timestamps {
+        for segment in result.segments.iter().filter(|segment| !segment.text.trim().is_empty()) {
+            let _ = writeln!(output, "**{}** {}\n", format_marker(segment.t0), segment.text.trim());
+        }
+    } else {
+        let _ = writeln!(output, "{}", result.text.trim());
+    }
+    output
+}
+
+/// Render a transcription in the given format. Returns the text and the
+/// number of cues (subtitles) or segments it contains.
+pub fn render_transcript(
+    result: &TranscriptionResult,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Result<(String, usize), String> {
+    options.validate()?;
+
+    match format {
+        ExportFormat::Srt | ExportFormat::Vtt => {
+            let cues = build_cues(&result.segments, options);
+            let output = if format == ExportFormat::Srt { render_srt(&cues) } else { render_vtt(&cues) };
+            Ok((output, cues.len()))
+        }
+        ExportFormat::Txt => Ok((render_txt(result, options), result.segments.len())),
+        ExportFormat::Json => {
+            let output = serde_json::to_string_pretty(result)
+                .map_err(|e| format!("Failed to serialize transcript: {}", e))?;
+            Ok((output, result.segments.len()))
+        }
+        ExportFormat::Markdown => Ok((render_markdown(result, options), result.segments.len())),
+    }
+}
+
+fn write_export(
+    result: &TranscriptionResult,
+    format: ExportFormat,
+    path: &str,
+    options: &ExportOptions,
+) -> Result<ExportSummary, String> {
+    let (output, cues) = render_transcript(result, format, options)?;
+    fs::write(Path::new(path), &output).map_err(|e| format!("Failed to write export file: {}", e))?;
+
+    Ok(ExportSummary {
+        path: path.to_string(),
+        bytes: output.len() as u64,
+        cues,
+    })
+}
+
+/// Write a transcription to `path` as SRT, WebVTT, plain text, JSON or Markdown
+#[tauri::command]
+pub async fn export_transcript(
+    result: TranscriptionResult,
+    format: ExportFormat,
+    path: String,
+    options: Option<ExportOptions>,
+) -> Result<ExportSummary, String> {
+    let options = options.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || write_export(&result, format, &path, &options))
+        .await
+        .map_err(|e| format!("Export task failed: {}", e))?
+}
+
+/// Export a transcript stored in the history without sending it through the frontend
+#[tauri::command]
+pub async fn export_history_transcript(
+    transcript_id: i64,
+    format: ExportFormat,
+    path: String,
+    options: Option<ExportOptions>,
+    app_handle: AppHandle,
+) -> Result<ExportSummary, String> {
+    let options = options.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        let transcript = with_database(&app_handle, |connection| load_transcript(connection, transcript_id))?
+            .ok_or_else(|| format!("Transcript not found: {}", transcript_id))?;
+        let result = TranscriptionResult {
+            text: transcript.text,
+            segments: transcript.segments,
+            language: transcript.language.unwrap_or_default(),
+            language_probability: None,
+        };
+        write_export(&result, format, &path, &options)
+    })
+    .await
+    .map_err(|e| format!("Export task failed: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(t0: i64, t1: i64, text: &str) -> TranscriptionSegment {
+        TranscriptionSegment {
+            t0,
+            t1,
+            text: text.to_string(),
+            no_speech_probability: 0.0,
+            words: Vec::new(),
+        }
+    }
+
+    fn options(max_line_length: usize, max_lines: usize, max_cue_duration_ms: i64) -> ExportOptions {
+        ExportOptions {
+            max_line_length,
+            max_lines,
+            max_cue_duration_ms,
+            ..ExportOptions::default()
+        }
+    }
+
+    /// Cues must follow each other without gaps from `t0` to `t1`
+    fn assert_contiguous(cues: &[SubtitleCue], t0: i64, t1: i64) {
+        assert_eq!(cues.first().unwrap().start_ms, t0);
+        assert_eq!(cues.last().unwrap().end_ms, t1);
+        for pair in cues.windows(2) {
+            assert_eq!(pair[0].end_ms, pair[1].start_ms);
+        }
+    }
+
+    #[test]
+    fn words_wrap_greedily() {
+        assert_eq!(wrap_words("the quick brown fox jumps", 10), vec!["the quick", "brown fox", "jumps"]);
+        assert_eq!(wrap_words("  spaced   out  ", 20), vec!["spaced out"]);
+        assert!(wrap_words("   ", 20).is_empty());
+        // Longer than a line: kept whole on a line of its own
+        assert_eq!(wrap_words("a extraordinarily b", 10), vec!["a", "extraordinarily", "b"]);
+        // Characters, not bytes
+        assert_eq!(wrap_words("über öfter", 10), vec!["über öfter"]);
+    }
+
+    #[test]
+    fn long_segment_is_split_across_cues() {
+        let segments = vec![segment(1000, 7000, "one two three four five six seven eight")];
+
+        let cues = build_cues(&segments, &options(10, 2, 7000));
+
+        let lines: Vec<Vec<&str>> = cues
+            .iter()
+            .map(|cue| cue.lines.iter().map(String::as_str).collect())
+            .collect();
+        assert_eq!(lines, vec![
+            vec!["one two", "three four"],
+            vec!["five six", "seven"],
+            vec!["eight"],
+        ]);
+        // Time is shared out by characters: 15, 12 and 5 of 32
+        assert_eq!((cues[0].end_ms, cues[1].end_ms), (3812, 6062));
+        assert_contiguous(&cues, 1000, 7000);
+    }
+
+    #[test]
+    fn overlong_cues_are_split_without_losing_time() {
+        let segments = vec![segment(0, 20_000, "slow words spoken over twenty seconds")];
+
+        let cues = build_cues(&segments, &options(42, 2, 7000));
+
+        // 11 of the 32 characters fit in 7 seconds
+        assert_eq!(cues.len(), 4);
+        assert_contiguous(&cues, 0, 20_000);
+        assert!(cues.iter().all(|cue| cue.end_ms - cue.start_ms <= 7000));
+        let text: Vec<String> = cues.iter().map(|cue| cue.lines.join(" ")).collect();
+        assert_eq!(text.join(" "), "slow words spoken over twenty seconds");
+    }
+
+    #[test]
+    fn a_single_word_keeps_its_whole_time() {
+        let cues = build_cues(&[segment(0, 9000, "Hmm")], &options(42, 2, 7000));
+        assert_eq!(cues, vec![SubtitleCue { start_ms: 0, end_ms: 9000, lines: vec!["Hmm".to_string()] }]);
+    }
+
+    #[test]
+    fn empty_segments_have_no_cues() {
+        assert!(build_cues(&[segment(0, 1000, " ")], &ExportOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn timestamps_use_the_format_separator() {
+        assert_eq!(format_timestamp(3_723_045, ','), "01:02:03,045");
+        assert_eq!(format_timestamp(3_723_045, '.'), "01:02:03.045");
+        assert_eq!(format_timestamp(-5, ','), "00:00:00,000");
+        assert_eq!(format_timestamp(100 * 3_600_000, '.'), "100:00:00.000");
+        assert_eq!(format_marker(3_723_999), "[01:02:03]");
+    }
+
+    #[test]
+    fn subtitles_render_as_srt_and_vtt() {
+        let cues = vec![
+            SubtitleCue { start_ms: 0, end_ms: 1500, lines: vec!["Hello".to_string(), "there".to_string()] },
+            SubtitleCue { start_ms: 1500, end_ms: 61_000, lines: vec!["a --> b".to_string()] },
+        ];
+
+        assert_eq!(
+            render_srt(&cues),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello\nthere\n\n2\n00:00:01,500 --> 00:01:01,000\na -> b\n\n"
+        );
+        assert_eq!(
+            render_vtt(&cues),
+            "WEBVTT\n\n00:00:00.000 --> 00:00:01.500\nHello\nthere\n\n00:00:01.500 --> 00:01:01.000\na -> b\n\n"
+        );
+    }
+}
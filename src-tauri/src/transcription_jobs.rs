@@ -0,0 +1,526 @@
+use once_cell::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+
+use crate::audio_conditioning::{condition_audio, AudioFormat};
+use crate::model_pool::acquire_model;
+use crate::whisper::{
+    ModelVariant, TranscriptionControl, TranscriptionOptions, TranscriptionResult,
+    TRANSCRIPTION_CANCELLED,
+};
+
+// Number of finished jobs kept around so their result can still be fetched
+const MAX_FINISHED_JOBS: usize = 20;
+
+// Whisper states allowed to run against each loaded context at once, until configured
+const DEFAULT_MAX_CONCURRENT: usize = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptionJobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl TranscriptionJobStatus {
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, TranscriptionJobStatus::Queued | TranscriptionJobStatus::Running)
+    }
+}
+
+/// Scheduling priority. Queued jobs start in priority order, then in the
+/// order they were enqueued.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptionPriority {
+    Dictation, // Hotkey dictation, the user is waiting for the text
+    #[default]
+    Normal,
+    Batch, // Re-transcription of existing recordings
+}
+
+impl TranscriptionPriority {
+    fn rank(&self) -> u8 {
+        match self {
+            TranscriptionPriority::Dictation => 0,
+            TranscriptionPriority::Normal => 1,
+            TranscriptionPriority::Batch => 2,
+        }
+    }
+}
+
+/// Snapshot of a job, also used as the `transcription-job` event payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionJobInfo {
+    pub job_id: String,
+    pub status: TranscriptionJobStatus,
+    pub priority: TranscriptionPriority,
+    pub variant: ModelVariant,
+    pub result: Option<TranscriptionResult>,
+    pub error: Option<String>,
+}
+
+/// Payload of the `transcription-queue` event and `get_transcription_queue_status`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionQueueStatus {
+    pub queued: usize,
+    pub running: usize,        // Across all models
+    pub max_concurrent: usize, // Per loaded model
+}
+
+type JobCompletion = oneshot::Sender<Result<TranscriptionResult, String>>;
+
+struct TranscriptionJob {
+    info: TranscriptionJobInfo,
+    sequence: u64,
+    cancel_flag: Arc<AtomicBool>,
+    completion: Option<JobCompletion>,
+}
+
+/// What a pending entry does once it gets a slot
+enum PendingWork {
+    /// A registered job, transcribed by `run_job`
+    Transcription {
+        audio_data: Vec<f32>,
+        options: TranscriptionOptions,
+    },
+    /// A caller that runs its own inference (streaming sessions). It is sent
+    /// the number of threads it may use.
+    Slot(oneshot::Sender<usize>),
+}
+
+/// Work that is waiting for a free slot
+struct PendingJob {
+    job_id: String,
+    priority: TranscriptionPriority,
+    sequence: u64,
+    variant: ModelVariant,
+    work: PendingWork,
+    app_handle: AppHandle,
+}
+
+struct JobRegistry {
+    jobs: HashMap<String, TranscriptionJob>,
+    pending: Vec<PendingJob>,
+    finished: VecDeque<String>,
+    running: HashMap<ModelVariant, usize>, // Running jobs per model context
+    max_concurrent: usize,
+}
+
+impl Default for JobRegistry {
+    fn default() -> Self {
+        JobRegistry {
+            jobs: HashMap::new(),
+            pending: Vec::new(),
+            finished: VecDeque::new(),
+            running: HashMap::new(),
+            max_concurrent: DEFAULT_MAX_CONCURRENT,
+        }
+    }
+}
+
+impl JobRegistry {
+    fn status(&self) -> TranscriptionQueueStatus {
+        TranscriptionQueueStatus {
+            queued: self.pending.len(),
+            running: self.running.values().sum(),
+            max_concurrent: self.max_concurrent,
+        }
+    }
+
+    fn running_on(&self, variant: &ModelVariant) -> usize {
+        self.running.get(variant).copied().unwrap_or(0)
+    }
+
+    /// Take pending jobs whose model has a free slot, highest priority first
+    fn take_runnable(&mut self) -> Vec<PendingJob> {
+        let mut runnable = Vec::new();
+
+        while let Some(next) = self
+            .pending
+            .iter()
+            .enumerate()
+            .filter(|(_, job)| self.running_on(&job.variant) < self.max_concurrent)
+            .min_by_key(|(_, job)| (job.priority.rank(), job.sequence))
+            .map(|(index, _)| index)
+        {
+            let job = self.pending.remove(next);
+            if let Some(entry) = self.jobs.get_mut(&job.job_id) {
+                entry.info.status = TranscriptionJobStatus::Running;
+            }
+            *self.running.entry(job.variant.clone()).or_default() += 1;
+            runnable.push(job);
+        }
+
+        runnable
+    }
+
+    /// Free the slot a job held on its model
+    fn release(&mut self, variant: &ModelVariant) {
+        if let Some(count) = self.running.get_mut(variant) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                self.running.remove(variant);
+            }
+        }
+    }
+
+    /// Record the terminal state of a job and hand its result to any waiter
+    fn finish(&mut self, info: TranscriptionJobInfo) {
+        let job_id = info.job_id.clone();
+        if let Some(job) = self.jobs.get_mut(&job_id) {
+            if let Some(completion) = job.completion.take() {
+                let outcome = match &info.result {
+                    Some(result) => Ok(result.clone()),
+                    None => Err(info.error.clone().unwrap_or_default()),
+                };
+                let _ = completion.send(outcome);
+            }
+            job.info = info;
+        }
+
+        self.finished.push_back(job_id);
+        while self.finished.len() > MAX_FINISHED_JOBS {
+            if let Some(oldest) = self.finished.pop_front() {
+                self.jobs.remove(&oldest);
+            }
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref TRANSCRIPTION_JOBS: Arc<Mutex<JobRegistry>> =
+        Arc::new(Mutex::new(JobRegistry::default()));
+}
+
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+fn job_info(job_id: &str) -> Option<TranscriptionJobInfo> {
+    let jobs = TRANSCRIPTION_JOBS.lock().unwrap();
+    jobs.jobs.get(job_id).map(|job| job.info.clone())
+}
+
+fn emit_queue_status(app_handle: &AppHandle) {
+    let status = TRANSCRIPTION_JOBS.lock().unwrap().status();
+    let _ = app_handle.emit("transcription-queue", status);
+}
+
+/// Start pending jobs while there are free slots
+fn dispatch(app_handle: &AppHandle) {
+    let (runnable, threads_per_job) = {
+        let mut jobs = TRANSCRIPTION_JOBS.lock().unwrap();
+        let threads_per_job = (num_cpus::get() / jobs.max_concurrent).max(1);
+        (jobs.take_runnable(), threads_per_job)
+    };
+
+    let mut abandoned = Vec::new();
+    for job in runnable {
+        match job.work {
+            PendingWork::Slot(waiter) => {
+                // The caller stopped waiting, so nobody will release the slot
+                if waiter.send(threads_per_job).is_err() {
+                    abandoned.push(job.variant);
+                }
+            }
+            PendingWork::Transcription { .. } => {
+                if let Some(info) = job_info(&job.job_id) {
+                    let _ = app_handle.emit("transcription-job", info);
+                }
+
+                tauri::async_runtime::spawn_blocking(move || run_job(job, threads_per_job));
+            }
+        }
+    }
+
+    if !abandoned.is_empty() {
+        let mut jobs = TRANSCRIPTION_JOBS.lock().unwrap();
+        for variant in &abandoned {
+            jobs.release(variant);
+        }
+        drop(jobs);
+        dispatch(app_handle);
+        return;
+    }
+
+    emit_queue_status(app_handle);
+}
+
+fn run_job(job: PendingJob, n_threads: usize) {
+    let PendingWork::Transcription { audio_data, options } = job.work else {
+        return;
+    };
+
+    let cancel_flag = {
+        let jobs = TRANSCRIPTION_JOBS.lock().unwrap();
+        jobs.jobs
+            .get(&job.job_id)
+            .map(|entry| entry.cancel_flag.clone())
+            .unwrap_or_else(|| Arc::new(AtomicBool::new(true)))
+    };
+
+    let control = TranscriptionControl {
+        app_handle: Some(job.app_handle.clone()),
+        job_id: Some(job.job_id.clone()),
+        cancel_flag: Some(cancel_flag.clone()),
+        n_threads: Some(n_threads),
+    };
+
+    // Loads the model from disk first if it is not in the pool (e.g. evicted)
+    let outcome = acquire_model(&job.variant, &job.app_handle)
+        .and_then(|ctx| ctx.transcribe_with_control(audio_data, &options, &control));
+
+    let base = TranscriptionJobInfo {
+        job_id: job.job_id,
+        status: TranscriptionJobStatus::Completed,
+        priority: job.priority,
+        variant: job.variant,
+        result: None,
+        error: None,
+    };
+    let info = match outcome {
+        Ok(result) => TranscriptionJobInfo {
+            result: Some(result),
+            ..base
+        },
+        Err(_) if cancel_flag.load(Ordering::SeqCst) => TranscriptionJobInfo {
+            status: TranscriptionJobStatus::Cancelled,
+            error: Some(TRANSCRIPTION_CANCELLED.to_string()),
+            ..base
+        },
+        Err(e) => TranscriptionJobInfo {
+            status: TranscriptionJobStatus::Failed,
+            error: Some(e),
+            ..base
+        },
+    };
+
+    {
+        let mut jobs = TRANSCRIPTION_JOBS.lock().unwrap();
+        jobs.release(&info.variant);
+        jobs.finish(info.clone());
+    }
+
+    let _ = job.app_handle.emit("transcription-job", info);
+    dispatch(&job.app_handle);
+}
+
+/// Put a job in the queue and start it if a slot is free
+fn enqueue(
+    audio_data: Vec<f32>,
+    variant: ModelVariant,
+    options: TranscriptionOptions,
+    priority: TranscriptionPriority,
+    completion: Option<JobCompletion>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    options.validate()?;
+
+    let sequence = NEXT_JOB_ID.fetch_add(1, Ordering::SeqCst);
+    let job_id = format!("job-{}", sequence);
+    let info = TranscriptionJobInfo {
+        job_id: job_id.clone(),
+        status: TranscriptionJobStatus::Queued,
+        priority,
+        variant: variant.clone(),
+        result: None,
+        error: None,
+    };
+
+    {
+        let mut jobs = TRANSCRIPTION_JOBS.lock().unwrap();
+        jobs.jobs.insert(
+            job_id.clone(),
+            TranscriptionJob {
+                info: info.clone(),
+                sequence,
+                cancel_flag: Arc::new(AtomicBool::new(false)),
+                completion,
+            },
+        );
+        jobs.pending.push(PendingJob {
+            job_id: job_id.clone(),
+            priority,
+            sequence,
+            variant,
+            work: PendingWork::Transcription { audio_data, options },
+            app_handle: app_handle.clone(),
+        });
+    }
+
+    let _ = app_handle.emit("transcription-job", info);
+    dispatch(&app_handle);
+
+    Ok(job_id)
+}
+
+/// A slot on a model's context held outside the job queue. Released, and the
+/// queue advanced, when dropped.
+pub(crate) struct TranscriptionSlot {
+    variant: ModelVariant,
+    app_handle: AppHandle,
+    pub n_threads: usize,
+}
+
+impl Drop for TranscriptionSlot {
+    fn drop(&mut self) {
+        TRANSCRIPTION_JOBS.lock().unwrap().release(&self.variant);
+        dispatch(&self.app_handle);
+    }
+}
+
+/// Wait for a free slot on `variant`, in the same priority order as queued
+/// jobs. Used by streaming sessions, which keep their own state between runs
+/// but must still respect the concurrency limit.
+pub(crate) async fn acquire_slot(
+    variant: ModelVariant,
+    priority: TranscriptionPriority,
+    app_handle: AppHandle,
+) -> Result<TranscriptionSlot, String> {
+    let (sender, receiver) = oneshot::channel();
+    let sequence = NEXT_JOB_ID.fetch_add(1, Ordering::SeqCst);
+
+    TRANSCRIPTION_JOBS.lock().unwrap().pending.push(PendingJob {
+        job_id: format!("slot-{}", sequence),
+        priority,
+        sequence,
+        variant: variant.clone(),
+        work: PendingWork::Slot(sender),
+        app_handle: app_handle.clone(),
+    });
+    dispatch(&app_handle);
+
+    let n_threads = receiver
+        .await
+        .map_err(|_| "Transcription slot request was dropped".to_string())?;
+
+    Ok(TranscriptionSlot {
+        variant,
+        app_handle,
+        n_threads,
+    })
+}
+
+/// Queue a transcription and wait for its result. Used by the synchronous
+/// `transcribe_audio` commands so they respect the concurrency limit too.
+pub(crate) async fn transcribe_queued(
+    audio_data: Vec<f32>,
+    variant: ModelVariant,
+    options: TranscriptionOptions,
+    priority: TranscriptionPriority,
+    app_handle: AppHandle,
+) -> Result<TranscriptionResult, String> {
+    let (sender, receiver) = oneshot::channel();
+    enqueue(audio_data, variant, options, priority, Some(sender), app_handle)?;
+
+    receiver
+        .await
+        .map_err(|_| "Transcription job was dropped".to_string())?
+}
+
+/// Queue a transcription and return its job ID. Progress and the outcome are
+/// reported through `transcription-progress` and `transcription-job` events.
+#[tauri::command]
+pub async fn start_transcription(
+    audio_data: Vec<f32>,
+    variant: ModelVariant,
+    options: Option<TranscriptionOptions>,
+    priority: Option<TranscriptionPriority>,
+    format: Option<AudioFormat>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let audio_data = condition_audio(&audio_data, &format.unwrap_or_default())?;
+    enqueue(
+        audio_data,
+        variant,
+        options.unwrap_or_default(),
+        priority.unwrap_or_default(),
+        None,
+        app_handle,
+    )
+}
+
+/// Cancel a job. Queued jobs are removed immediately; running jobs stop at
+/// whisper's next abort check. Both then report the `cancelled` status.
+#[tauri::command]
+pub async fn cancel_transcription(job_id: String, app_handle: AppHandle) -> Result<(), String> {
+    let cancelled = {
+        let mut jobs = TRANSCRIPTION_JOBS.lock().unwrap();
+        let (info, cancel_flag) = jobs
+            .jobs
+            .get(&job_id)
+            .map(|job| (job.info.clone(), job.cancel_flag.clone()))
+            .ok_or_else(|| format!("Unknown transcription job: {}", job_id))?;
+
+        match info.status {
+            TranscriptionJobStatus::Queued => {
+                let info = TranscriptionJobInfo {
+                    status: TranscriptionJobStatus::Cancelled,
+                    error: Some(TRANSCRIPTION_CANCELLED.to_string()),
+                    ..info
+                };
+                jobs.pending.retain(|pending| pending.job_id != job_id);
+                jobs.finish(info.clone());
+                Some(info)
+            }
+            TranscriptionJobStatus::Running => {
+                cancel_flag.store(true, Ordering::SeqCst);
+                None
+            }
+            _ => return Err(format!("Transcription job {} has already finished", job_id)),
+        }
+    };
+
+    if let Some(info) = cancelled {
+        let _ = app_handle.emit("transcription-job", info);
+        emit_queue_status(&app_handle);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_transcription_job(job_id: String) -> Result<TranscriptionJobInfo, String> {
+    job_info(&job_id).ok_or_else(|| format!("Unknown transcription job: {}", job_id))
+}
+
+/// List known jobs (queued, running and recently finished) in enqueue order
+#[tauri::command]
+pub async fn list_transcription_jobs() -> Result<Vec<TranscriptionJobInfo>, String> {
+    let jobs = TRANSCRIPTION_JOBS.lock().unwrap();
+    let mut entries: Vec<&TranscriptionJob> = jobs.jobs.values().collect();
+    entries.sort_by_key(|job| job.sequence);
+
+    Ok(entries.into_iter().map(|job| job.info.clone()).collect())
+}
+
+#[tauri::command]
+pub async fn get_transcription_queue_status() -> Result<TranscriptionQueueStatus, String> {
+    Ok(TRANSCRIPTION_JOBS.lock().unwrap().status())
+}
+
+/// Set how many whisper states may run concurrently against each loaded
+/// context. CPU threads are split evenly between a model's running jobs.
+#[tauri::command]
+pub async fn set_transcription_concurrency(
+    max_concurrent: usize,
+    app_handle: AppHandle,
+) -> Result<TranscriptionQueueStatus, String> {
+    if max_concurrent == 0 || max_concurrent > num_cpus::get() {
+        return Err(format!(
+            "Concurrency must be between 1 and {} (number of CPU cores)",
+            num_cpus::get()
+        ));
+    }
+
+    TRANSCRIPTION_JOBS.lock().unwrap().max_concurrent = max_concurrent;
+    dispatch(&app_handle);
+
+    get_transcription_queue_status().await
+}
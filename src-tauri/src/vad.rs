@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+
+// Analysis frame length; 30 ms is the usual choice for energy based detectors
+const FRAME_MS: usize = 30;
+
+/// Settings for the energy based voice activity detection that runs before
+/// whisper. Silence is trimmed from both ends and long recordings are split
+/// at pauses into chunks that are transcribed independently. Off unless
+/// `enabled` is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VadOptions {
+    pub enabled: bool,
+    pub threshold_ratio: f32,   // Speech threshold as a multiple of the estimated noise floor
+    pub min_threshold: f32,     // Absolute RMS floor so digital silence is never speech
+    pub min_silence_ms: usize,  // Shortest pause that may end a chunk
+    pub max_chunk_ms: usize,    // Chunks longer than this are split at the best pause
+    pub padding_ms: usize,      // Audio kept around detected speech
+}
+
+impl Default for VadOptions {
+    fn default() -> Self {
+        VadOptions {
+            enabled: false,
+            threshold_ratio: 3.0,
+            min_threshold: 0.002,
+            min_silence_ms: 500,
+            max_chunk_ms: 30_000,
+            padding_ms: 200,
+        }
+    }
+}
+
+impl VadOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.threshold_ratio.is_finite() || self.threshold_ratio < 1.0 {
+            return Err("VAD threshold ratio must be at least 1.0".to_string());
+        }
+        if !self.min_threshold.is_finite() || self.min_threshold < 0.0 {
+            return Err("VAD minimum threshold must not be negative".to_string());
+        }
+        if self.max_chunk_ms < 1_000 {
+            return Err("VAD maximum chunk length must be at least 1000 ms".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A range of samples that contains speech
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpeechChunk {
+    pub start: usize, // First sample (inclusive)
+    pub end: usize,   // Last sample (exclusive)
+}
+
+impl SpeechChunk {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// Offset of the chunk in the original recording, in milliseconds
+    pub fn offset_ms(&self, sample_rate: usize) -> i64 {
+        (self.start * 1000 / sample_rate) as i64
+    }
+}
+
+fn frame_energies(audio: &[f32], frame_len: usize) -> Vec<f32> {
+    audio
+        .chunks(frame_len)
+        .map(|frame| {
+            let sum: f32 = frame.iter().map(|sample| sample * sample).sum();
+            (sum / frame.len() as f32).sqrt()
+        })
+        .collect()
+}
+
+/// Estimate the noise floor as the 10th percentile of frame energies
+fn noise_floor(energies: &[f32]) -> f32 {
+    let mut sorted = energies.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    sorted[sorted.len() / 10]
+}
+
+/// Find the speech in `audio` and return the chunks to transcribe, in order.
+/// Returns an empty list when no frame exceeds the speech threshold.
+pub fn detect_speech_chunks(audio: &[f32], sample_rate: usize, options: &VadOptions) -> Vec<SpeechChunk> {
+    if audio.is_empty() {
+        return Vec::new();
+    }
+
+    if !options.enabled {
+        return vec![SpeechChunk { start: 0, end: audio.len() }];
+    }
+
+    let frame_len = (sample_rate * FRAME_MS / 1000).max(1);
+    let energies = frame_energies(audio, frame_len);
+    let threshold = (noise_floor(&energies) * options.threshold_ratio).max(options.min_threshold);
+    let is_speech: Vec<bool> = energies.iter().map(|energy| *energy > threshold).collect();
+
+    let min_silence_frames = (options.min_silence_ms / FRAME_MS).max(1);
+    let max_chunk_frames = (options.max_chunk_ms / FRAME_MS).max(1);
+    let padding_frames = options.padding_ms / FRAME_MS;
+
+    // Group speech frames into regions, bridging pauses shorter than min_silence
+    let mut regions: Vec<(usize, usize)> = Vec::new();
+    for (frame, speech) in is_speech.iter().enumerate() {
+        if !speech {
+            continue;
+        }
+        match regions.last_mut() {
+            Some((_, end)) if frame - *end < min_silence_frames => *end = frame + 1,
+            _ => regions.push((frame, frame + 1)),
+        }
+    }
+
+    // Pad each region, then merge neighbours while they fit in one chunk.
+    // Padding never reaches back into the previous chunk, so no audio is
+    // transcribed twice when padding exceeds half the minimum pause.
+    let frame_count = energies.len();
+    let mut chunks: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in regions {
+        let start = start.saturating_sub(padding_frames);
+        let end = (end + padding_frames).min(frame_count);
+
+        match chunks.last_mut() {
+            Some((chunk_start, chunk_end)) if end - *chunk_start <= max_chunk_frames => {
+                *chunk_end = end.max(*chunk_end);
+            }
+            Some((_, chunk_end)) => {
+                let start = start.max(*chunk_end);
+                chunks.push((start, end));
+            }
+            None => chunks.push((start, end)),
+        }
+    }
+
+    // A single region can still exceed the limit; cut it at its quietest frames
+    let mut split = Vec::new();
+    for (start, end) in chunks {
+        split_at_quietest(&energies, start, end, max_chunk_frames, &mut split);
+    }
+
+    split
+        .into_iter()
+        .map(|(start, end)| SpeechChunk {
+            start: start * frame_len,
+            end: (end * frame_len).min(audio.len()),
+        })
+        .filter(|chunk| !chunk.is_empty())
+        .collect()
+}
+
+fn split_at_quietest(
+    energies: &[f32],
+    start: usize,
+    end: usize,
+    max_frames: usize,
+    out: &mut Vec<(usize, usize)>,
+) {
+    if end - start <= max_frames {
+        out.push((start, end));
+        return;
+    }
+
+    // Search the second half of the allowed window so chunks stay reasonably long
+    let search_start = start + (max_frames / 2).max(1);
+    let search_end = start + max_frames;
+    let cut = (search_start..search_end)
+        .min_by(|a, b| {
+            energies[*a]
+                .partial_cmp(&energies[*b])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .unwrap_or(search_end);
+
+    out.push((start, cut));
+    split_at_quietest(energies, cut, end, max_frames, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 16_000;
+    const FRAME: usize = SAMPLE_RATE * FRAME_MS / 1000;
+
+    /// Silence with a 440 Hz tone over each of the `(start_ms, end_ms)` ranges
+    fn tones(length_ms: usize, ranges: &[(usize, usize)]) -> Vec<f32> {
+        let mut audio = vec![0.0; length_ms * SAMPLE_RATE / 1000];
+        for &(start_ms, end_ms) in ranges {
+            let start = start_ms * SAMPLE_RATE / 1000;
+            let end = end_ms * SAMPLE_RATE / 1000;
+            for (offset, sample) in audio[start..end].iter_mut().enumerate() {
+                let t = (start + offset) as f32 / SAMPLE_RATE as f32;
+                *sample = 0.5 * (2.0 * std::f32::consts::PI * 440.0 * t).sin();
+            }
+        }
+        audio
+    }
+
+    fn enabled() -> VadOptions {
+        VadOptions {
+            enabled: true,
+            ..VadOptions::default()
+        }
+    }
+
+    #[test]
+    fn disabled_by_default_and_keeps_everything() {
+        let audio = tones(3000, &[(1000, 2000)]);
+        let chunks = detect_speech_chunks(&audio, SAMPLE_RATE, &VadOptions::default());
+        assert_eq!(chunks, vec![SpeechChunk { start: 0, end: audio.len() }]);
+    }
+
+    #[test]
+    fn silence_has_no_chunks() {
+        let audio = tones(3000, &[]);
+        assert!(detect_speech_chunks(&audio, SAMPLE_RATE, &enabled()).is_empty());
+    }
+
+    #[test]
+    fn neighbouring_speech_merges_into_one_padded_chunk() {
+        let audio = tones(5000, &[(1000, 2000), (3000, 4000)]);
+
+        let chunks = detect_speech_chunks(&audio, SAMPLE_RATE, &enabled());
+
+        // Speech covers frames 33..67 and 100..134, padded by 6 frames each side
+        assert_eq!(chunks, vec![SpeechChunk { start: 27 * FRAME, end: 140 * FRAME }]);
+    }
+
+    #[test]
+    fn chunks_are_split_at_pauses_when_too_long() {
+        let audio = tones(5000, &[(1000, 2000), (3000, 4000)]);
+        let options = VadOptions {
+            max_chunk_ms: 2000,
+            ..enabled()
+        };
+
+        let chunks = detect_speech_chunks(&audio, SAMPLE_RATE, &options);
+
+        assert_eq!(chunks, vec![
+            SpeechChunk { start: 27 * FRAME, end: 73 * FRAME },
+            SpeechChunk { start: 94 * FRAME, end: 140 * FRAME },
+        ]);
+        assert_eq!(chunks[1].offset_ms(SAMPLE_RATE), 2820);
+    }
+
+    #[test]
+    fn padding_never_overlaps_the_previous_chunk() {
+        let audio = tones(5000, &[(1000, 2000), (3000, 4000)]);
+        let options = VadOptions {
+            max_chunk_ms: 2000,
+            padding_ms: 1000,
+            ..enabled()
+        };
+
+        let chunks = detect_speech_chunks(&audio, SAMPLE_RATE, &options);
+
+        assert!(chunks.len() >= 2);
+        for pair in chunks.windows(2) {
+            assert!(pair[0].end <= pair[1].start);
+        }
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 66 * FRAME));
+    }
+
+    #[test]
+    fn continuous_speech_is_cut_without_gaps() {
+        // The leading silence sets the noise floor
+        let audio = tones(3500, &[(500, 3500)]);
+        let options = VadOptions {
+            max_chunk_ms: 1000,
+            ..enabled()
+        };
+
+        let chunks = detect_speech_chunks(&audio, SAMPLE_RATE, &options);
+
+        assert!(chunks.len() >= 3);
+        assert_eq!(chunks.first().unwrap().start, 10 * FRAME);
+        assert_eq!(chunks.last().unwrap().end, audio.len());
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 33 * FRAME));
+    }
+}
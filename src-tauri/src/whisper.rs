@@ -1,29 +1,94 @@
 use once_cell::sync::Mutex;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
-use whisper_rs::{WhisperContext as WhisperRsContext, WhisperContextParameters, FullParams, SamplingStrategy};
+use whisper_rs::{
+    WhisperContext as WhisperRsContext, WhisperContextParameters, WhisperState,
+    SegmentCallbackData, WhisperTokenId,
+};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+use crate::audio_conditioning::{condition_audio, AudioFormat};
+use crate::custom_models::CUSTOM_ID_PREFIX;
+use crate::decoding::{DecodingConfig, DecodingPreset};
+use crate::model_catalog::{model_filename, model_languages, model_memory_requirement, LanguageSupport};
+use crate::model_pool::{acquire_model, get_model};
+use crate::transcription_jobs::{acquire_slot, transcribe_queued, TranscriptionPriority};
+use crate::vad::{detect_speech_chunks, VadOptions};
+
+/// A Whisper model. Family, size, quantization and language scope of each
+/// variant are recorded in the model catalog, or in the custom model registry
+/// for imported models.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ModelVariant {
+    #[serde(rename = "tiny")]
     Tiny,
+    #[serde(rename = "tiny.en")]
+    TinyEn,
+    #[serde(rename = "tiny-q5_1")]
+    TinyQ5,
+    #[serde(rename = "base")]
     Base,
+    #[serde(rename = "base.en")]
+    BaseEn,
+    #[serde(rename = "base-q5_1")]
+    BaseQ5,
+    #[serde(rename = "base.en-q5_1")]
+    BaseEnQ5,
+    #[serde(rename = "small")]
     Small,
+    #[serde(rename = "small.en")]
+    SmallEn,
+    #[serde(rename = "small-q5_1")]
+    SmallQ5,
+    #[serde(rename = "medium")]
     Medium,
-    Large,
+    #[serde(rename = "medium.en")]
+    MediumEn,
+    #[serde(rename = "medium-q5_0")]
+    MediumQ5,
+    #[serde(rename = "large")]
+    Large, // large-v3
+    #[serde(rename = "large-v3-q5_0")]
+    LargeQ5,
+    #[serde(rename = "large-v3-turbo")]
+    LargeTurbo,
+    #[serde(rename = "large-v3-turbo-q5_0")]
+    LargeTurboQ5,
+    #[serde(rename = "large-v3-turbo-q8_0")]
+    LargeTurboQ8,
+    #[serde(rename = "distil-large-v3")]
+    DistilLarge,
+    #[serde(untagged, deserialize_with = "custom_model_id")]
+    Custom(String), // ID of a model added with `import_model`
+}
+
+/// Accept only IDs handed out by `import_model`, so a misspelled built-in
+/// variant is rejected instead of being looked up as a custom model
+fn custom_model_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let id = String::deserialize(deserializer)?;
+    if !id.starts_with(CUSTOM_ID_PREFIX) {
+        return Err(serde::de::Error::custom(format!("Unknown model variant: {}", id)));
+    }
+    Ok(id)
 }
 
 impl ModelVariant {
     pub fn to_filename(&self) -> String {
-        match self {
-            ModelVariant::Tiny => "ggml-tiny.bin".to_string(),
-            ModelVariant::Base => "ggml-base.bin".to_string(),
-            ModelVariant::Small => "ggml-small.bin".to_string(),
-            ModelVariant::Medium => "ggml-medium.bin".to_string(),
-            ModelVariant::Large => "ggml-large-v3.bin".to_string(),
-        }
+        model_filename(self)
+    }
+
+    /// Approximate memory needed to hold the model and one inference state, in bytes
+    pub fn estimated_memory(&self) -> u64 {
+        model_memory_requirement(self)
+    }
+
+    /// Whether the model can transcribe languages other than English
+    pub fn is_multilingual(&self) -> bool {
+        model_languages(self) == LanguageSupport::Multilingual
     }
 }
 
@@ -34,8 +99,256 @@ pub struct WhisperContext {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptionProgress {
+    pub job_id: Option<String>,         // Set when the transcription runs as a job
     pub stage: String,
-    pub progress: f32,
+    pub progress: f32,                  // 0.0 to 1.0
+    pub elapsed_ms: u64,                // Time since the transcription started
+    pub eta_ms: Option<u64>,            // Estimated time remaining, once whisper reported progress
+    pub latest_segment: Option<String>, // Most recently decoded segment text
+}
+
+/// Emits `transcription-progress` events with timing information.
+/// Cheap to clone so whisper's progress and segment callbacks can own a copy.
+#[derive(Clone)]
+struct ProgressReporter {
+    app_handle: Option<AppHandle>,
+    job_id: Option<String>,
+    started: Instant,
+    state: Arc<Mutex<ProgressState>>,
+}
+
+#[derive(Default)]
+struct ProgressState {
+    progress: f32,
+    latest_segment: Option<String>,
+}
+
+impl ProgressReporter {
+    fn new(app_handle: Option<AppHandle>, job_id: Option<String>) -> Self {
+        ProgressReporter {
+            app_handle,
+            job_id,
+            started: Instant::now(),
+            state: Arc::new(Mutex::new(ProgressState::default())),
+        }
+    }
+
+    fn report(&self, stage: &str, progress: f32) {
+        let latest_segment = {
+            let mut state = self.state.lock().unwrap();
+            state.progress = progress;
+            state.latest_segment.clone()
+        };
+        self.emit(stage, progress, latest_segment);
+    }
+
+    fn report_segment(&self, text: String) {
+        let progress = {
+            let mut state = self.state.lock().unwrap();
+            state.latest_segment = Some(text.clone());
+            state.progress
+        };
+        self.emit("processing_audio", progress, Some(text));
+    }
+
+    fn emit(&self, stage: &str, progress: f32, latest_segment: Option<String>) {
+        let Some(app) = &self.app_handle else {
+            return;
+        };
+
+        let elapsed = self.started.elapsed();
+        let eta_ms = if progress >= 1.0 {
+            Some(0)
+        } else if progress > 0.0 {
+            let remaining = elapsed.as_secs_f32() * (1.0 - progress) / progress;
+            Some((remaining * 1000.0) as u64)
+        } else {
+            None
+        };
+
+        let _ = app.emit("transcription-progress", TranscriptionProgress {
+            job_id: self.job_id.clone(),
+            stage: stage.to_string(),
+            progress,
+            elapsed_ms: elapsed.as_millis() as u64,
+            eta_ms,
+            latest_segment,
+        });
+    }
+}
+
+/// A single segment of transcribed text with its position in the audio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionSegment {
+    pub t0: i64,                     // Segment start in milliseconds
+    pub t1: i64,                     // Segment end in milliseconds
+    pub text: String,
+    pub no_speech_probability: f32,  // Probability that the segment contains no speech
+    #[serde(default)]
+    pub words: Vec<TranscriptionWord>, // Empty unless word timestamps were requested
+}
+
+impl TranscriptionSegment {
+    /// Move the segment and its words by `offset_ms` on the timeline
+    pub fn shift(&mut self, offset_ms: i64) {
+        self.t0 += offset_ms;
+        self.t1 += offset_ms;
+        for word in self.words.iter_mut() {
+            word.t0 += offset_ms;
+            word.t1 += offset_ms;
+        }
+    }
+}
+
+/// A word with its timing and the decoder's confidence in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionWord {
+    pub text: String,
+    pub t0: i64,          // Word start in milliseconds
+    pub t1: i64,          // Word end in milliseconds
+    pub probability: f32, // Mean probability of the word's tokens, 0.0 to 1.0
+}
+
+/// Structured transcription output, keeping whisper's segment boundaries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionResult {
+    pub text: String,
+    pub segments: Vec<TranscriptionSegment>,
+    pub language: String,                  // Language code used for decoding
+    pub language_probability: Option<f32>, // Set only when the language was auto-detected
+}
+
+/// Language and task settings for a transcription run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "TranscriptionOptionsInput")]
+pub struct TranscriptionOptions {
+    pub language: String, // ISO 639-1 code (e.g. "de") or "auto" to detect
+    pub translate: bool,  // Translate the output to English
+    pub vad: VadOptions,  // Silence trimming and chunking before decoding
+    pub word_timestamps: bool, // Fill in per-word timing and confidence on each segment
+    pub preset: Option<DecodingPreset>, // Starting point for `decoding`, balanced if unset
+    pub decoding: DecodingConfig, // Search strategy, thresholds and threads
+}
+
+impl Default for TranscriptionOptions {
+    fn default() -> Self {
+        TranscriptionOptions {
+            language: "en".to_string(),
+            translate: false,
+            vad: VadOptions::default(),
+            word_timestamps: false,
+            preset: None,
+            decoding: DecodingConfig::default(),
+        }
+    }
+}
+
+/// `TranscriptionOptions` as sent by the frontend, where `decoding` only
+/// lists the fields that differ from the preset
+#[derive(Deserialize)]
+#[serde(default)]
+struct TranscriptionOptionsInput {
+    language: String,
+    translate: bool,
+    vad: VadOptions,
+    word_timestamps: bool,
+    preset: Option<DecodingPreset>,
+    decoding: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Default for TranscriptionOptionsInput {
+    fn default() -> Self {
+        let options = TranscriptionOptions::default();
+        TranscriptionOptionsInput {
+            language: options.language,
+            translate: options.translate,
+            vad: options.vad,
+            word_timestamps: options.word_timestamps,
+            preset: None,
+            decoding: serde_json::Map::new(),
+        }
+    }
+}
+
+impl TryFrom<TranscriptionOptionsInput> for TranscriptionOptions {
+    type Error = String;
+
+    fn try_from(input: TranscriptionOptionsInput) -> Result<Self, String> {
+        let preset = input.preset.unwrap_or(DecodingPreset::Balanced);
+        Ok(TranscriptionOptions {
+            language: input.language,
+            translate: input.translate,
+            vad: input.vad,
+            word_timestamps: input.word_timestamps,
+            preset: input.preset,
+            decoding: DecodingConfig::with_overrides(preset, input.decoding)?,
+        })
+    }
+}
+
+impl TranscriptionOptions {
+    pub fn is_auto_language(&self) -> bool {
+        self.language.eq_ignore_ascii_case("auto")
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.vad.validate()?;
+        self.decoding.validate()?;
+
+        if self.is_auto_language() {
+            return Ok(());
+        }
+
+        if self.language.contains('\0') {
+            return Err("Transcription language contains an invalid character".to_string());
+        }
+
+        whisper_rs::get_lang_id(&self.language.to_lowercase())
+            .map(|_| ())
+            .ok_or_else(|| format!("Unsupported transcription language: {}", self.language))
+    }
+
+    /// These options adjusted to what `variant` can do. English-only models
+    /// decode "auto" as English and reject other languages.
+    pub fn for_model(&self, variant: &ModelVariant) -> Result<TranscriptionOptions, String> {
+        if variant.is_multilingual() {
+            return Ok(self.clone());
+        }
+        if !self.is_auto_language() && !self.language.eq_ignore_ascii_case("en") {
+            return Err(format!(
+                "The {} model only transcribes English, not {}",
+                variant.to_filename(),
+                self.language
+            ));
+        }
+        if self.translate {
+            return Err(format!("The {} model cannot translate", variant.to_filename()));
+        }
+
+        Ok(TranscriptionOptions {
+            language: "en".to_string(),
+            ..self.clone()
+        })
+    }
+}
+
+/// Per-run hooks for a transcription: where to emit events, which job the
+/// run belongs to, and a flag that aborts whisper when set.
+#[derive(Clone, Default)]
+pub struct TranscriptionControl {
+    pub app_handle: Option<AppHandle>,
+    pub job_id: Option<String>,
+    pub cancel_flag: Option<Arc<AtomicBool>>,
+    pub n_threads: Option<usize>, // Defaults to all cores
+}
+
+impl TranscriptionControl {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_flag
+            .as_ref()
+            .map(|flag| flag.load(Ordering::SeqCst))
+            .unwrap_or(false)
+    }
 }
 
 impl WhisperContext {
@@ -49,31 +362,57 @@ impl WhisperContext {
         Ok(WhisperContext { ctx, variant })
     }
 
-    pub fn transcribe(&self, audio_data: Vec<f32>, app_handle: Option<AppHandle>) -> Result<String, String> {
-        // Emit progress: Loading model stage
-        if let Some(app) = &app_handle {
-            let _ = app.emit("transcription-progress", TranscriptionProgress {
-                stage: "loading_model".to_string(),
-                progress: 0.0,
-            });
+    pub fn transcribe(
+        &self,
+        audio_data: Vec<f32>,
+        options: &TranscriptionOptions,
+        app_handle: Option<AppHandle>,
+    ) -> Result<String, String> {
+        self.transcribe_segments(audio_data, options, app_handle)
+            .map(|result| result.text)
+    }
+
+    pub fn transcribe_segments(
+        &self,
+        audio_data: Vec<f32>,
+        options: &TranscriptionOptions,
+        app_handle: Option<AppHandle>,
+    ) -> Result<TranscriptionResult, String> {
+        let control = TranscriptionControl {
+            app_handle,
+            ..Default::default()
+        };
+        self.transcribe_with_control(audio_data, options, &control)
+    }
+
+    pub fn transcribe_with_control(
+        &self,
+        audio_data: Vec<f32>,
+        options: &TranscriptionOptions,
+        control: &TranscriptionControl,
+    ) -> Result<TranscriptionResult, String> {
+        options.validate()?;
+        let options = &options.for_model(&self.variant)?;
+
+        if control.is_cancelled() {
+            return Err(TRANSCRIPTION_CANCELLED.to_string());
         }
 
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-        
-        // Configure parameters for better transcription
-        params.set_n_threads(num_cpus::get() as i32);
-        params.set_translate(false);
-        params.set_language(Some("en"));
-        params.set_print_special(false);
-        params.set_print_progress(false);
-        params.set_print_realtime(false);
-        params.set_print_timestamps(false);
+        let reporter = ProgressReporter::new(control.app_handle.clone(), control.job_id.clone());
+        reporter.report("loading_model", 0.0);
 
-        // Emit progress: Processing audio stage
-        if let Some(app) = &app_handle {
-            let _ = app.emit("transcription-progress", TranscriptionProgress {
-                stage: "processing_audio".to_string(),
-                progress: 0.33,
+        // The decoding config may use fewer threads than the job was given, never more
+        let n_threads = options.decoding.threads(control.n_threads.unwrap_or_else(num_cpus::get));
+
+        // Trim silence and split long recordings at pauses
+        let chunks = detect_speech_chunks(&audio_data, SAMPLE_RATE, &options.vad);
+        if chunks.is_empty() {
+            reporter.report("complete", 1.0);
+            return Ok(TranscriptionResult {
+                text: String::new(),
+                segments: Vec::new(),
+                language: options.language.to_lowercase(),
+                language_probability: None,
             });
         }
 
@@ -81,93 +420,809 @@ impl WhisperContext {
         let mut state = self.ctx.create_state()
             .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
 
-        // Run the transcription
-        state.full(params, &audio_data)
-            .map_err(|e| format!("Transcription failed: {}", e))?;
+        // Resolve the decoding language on the first speech, running whisper's detector for "auto"
+        let first = chunks[0];
+        let (language, language_probability) =
+            resolve_language(&mut state, &audio_data[first.start..first.end], options, n_threads)?;
 
-        // Emit progress: Finalizing stage
-        if let Some(app) = &app_handle {
-            let _ = app.emit("transcription-progress", TranscriptionProgress {
-                stage: "finalizing".to_string(),
-                progress: 0.66,
+        let speech_samples: usize = chunks.iter().map(|chunk| chunk.len()).sum();
+        let mut processed_samples = 0;
+        let mut segments = Vec::new();
+
+        reporter.report("processing_audio", 0.0);
+
+        for chunk in &chunks {
+            if control.is_cancelled() {
+                return Err(TRANSCRIPTION_CANCELLED.to_string());
+            }
+
+            let mut params = options.decoding.full_params(n_threads);
+            params.set_translate(options.translate);
+            params.set_language(Some(&language));
+            params.set_no_context(true); // Chunks are transcribed independently
+            params.set_token_timestamps(options.word_timestamps);
+            params.set_print_special(false);
+            params.set_print_progress(false);
+            params.set_print_realtime(false);
+            params.set_print_timestamps(false);
+
+            // Forward whisper's own progress (in percent), scaled to this chunk's share of the speech
+            let base = processed_samples as f32 / speech_samples as f32;
+            let weight = chunk.len() as f32 / speech_samples as f32;
+            let progress_reporter = reporter.clone();
+            params.set_progress_callback_safe(move |percent: i32| {
+                let fraction = percent.clamp(0, 100) as f32 / 100.0;
+                progress_reporter.report("processing_audio", base + weight * fraction);
+            });
+            let segment_reporter = reporter.clone();
+            params.set_segment_callback_safe_lossy(move |data: SegmentCallbackData| {
+                segment_reporter.report_segment(data.text.trim().to_string());
             });
+
+            // Let whisper poll the cancel flag between decoding steps
+            if let Some(flag) = control.cancel_flag.clone() {
+                params.set_abort_callback_safe(move || flag.load(Ordering::SeqCst));
+            }
+
+            // Run the transcription
+            let outcome = state.full(params, &audio_data[chunk.start..chunk.end]);
+            if control.is_cancelled() {
+                return Err(TRANSCRIPTION_CANCELLED.to_string());
+            }
+            outcome.map_err(|e| format!("Transcription failed: {}", e))?;
+
+            // Extract the segments and map them back onto the original timeline
+            let offset_ms = chunk.offset_ms(SAMPLE_RATE);
+            let word_tokens = options.word_timestamps.then(|| self.ctx.token_eot());
+            segments.extend(collect_segments(&state, word_tokens)?.into_iter().map(|mut segment| {
+                segment.shift(offset_ms);
+                segment
+            }));
+
+            processed_samples += chunk.len();
         }
 
-        // Extract the transcribed text
-        let num_segments = state.full_n_segments()
-            .map_err(|e| format!("Failed to get segment count: {}", e))?;
+        reporter.report("finalizing", 1.0);
+        reporter.report("complete", 1.0);
 
-        let mut result = String::new();
-        for i in 0..num_segments {
-            let segment = state.full_get_segment_text(i)
-                .map_err(|e| format!("Failed to get segment text: {}", e))?;
-            result.push_str(&segment);
-            result.push(' ');
+        Ok(TranscriptionResult {
+            text: join_segment_text(&segments),
+            segments,
+            language,
+            language_probability,
+        })
+    }
+
+    /// Transcribe a short window of audio without emitting progress events.
+    /// Used by streaming sessions, which re-run inference as audio arrives.
+    fn transcribe_window(
+        &self,
+        audio_data: &[f32],
+        language: &str,
+        options: &TranscriptionOptions,
+        n_threads: usize,
+    ) -> Result<Vec<TranscriptionSegment>, String> {
+        let mut state = self.ctx.create_state()
+            .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
+
+        let mut params = options.decoding.full_params(options.decoding.threads(n_threads));
+        params.set_translate(options.translate);
+        params.set_language(Some(language));
+        params.set_no_context(true);
+        params.set_token_timestamps(options.word_timestamps);
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+
+        state.full(params, audio_data)
+            .map_err(|e| format!("Transcription failed: {}", e))?;
+
+        collect_segments(&state, options.word_timestamps.then(|| self.ctx.token_eot()))
+    }
+
+    /// Run a short inference on silence so the first real transcription
+    /// does not pay for lazy allocations and kernel setup
+    pub fn warm_up(&self) -> Result<(), String> {
+        let silence = vec![0.0f32; SAMPLE_RATE];
+        self.transcribe_window(&silence, "en", &TranscriptionOptions::default(), num_cpus::get()).map(|_| ())
+    }
+
+    pub fn variant(&self) -> &ModelVariant {
+        &self.variant
+    }
+}
+
+/// Resolve the language to decode with. For "auto" whisper's detector is run
+/// and its probability is returned alongside the detected code.
+fn resolve_language(
+    state: &mut WhisperState,
+    audio_data: &[f32],
+    options: &TranscriptionOptions,
+    n_threads: usize,
+) -> Result<(String, Option<f32>), String> {
+    if options.is_auto_language() {
+        let (language, probability) = detect_language(state, audio_data, n_threads)?;
+        Ok((language, Some(probability)))
+    } else {
+        Ok((options.language.to_lowercase(), None))
+    }
+}
+
+/// Extract all segments from a finished state (whisper timestamps are in centiseconds).
+/// With `word_tokens` set to the end-of-text token, words are assembled from
+/// the text tokens below it; everything from there on is a special token.
+fn collect_segments(
+    state: &WhisperState,
+    word_tokens: Option<WhisperTokenId>,
+) -> Result<Vec<TranscriptionSegment>, String> {
+    let mut segments = Vec::new();
+    for segment in state.as_iter() {
+        let segment_text = segment.to_str_lossy()
+            .map_err(|e| format!("Failed to get segment text: {}", e))?;
+        let t0 = segment.start_timestamp() * 10;
+        let t1 = segment.end_timestamp() * 10;
+
+        let words = match word_tokens {
+            Some(token_eot) => {
+                let mut tokens = Vec::new();
+                for index in 0..segment.n_tokens() {
+                    let Some(token) = segment.get_token(index) else { continue };
+                    let data = token.token_data();
+                    if data.id >= token_eot {
+                        continue;
+                    }
+                    let text = token.to_str_lossy()
+                        .map_err(|e| format!("Failed to get token text: {}", e))?;
+                    tokens.push(TokenTiming {
+                        text: text.into_owned(),
+                        t0: data.t0 * 10,
+                        t1: data.t1 * 10,
+                        probability: data.p,
+                    });
+                }
+                group_words(&tokens, t0, t1)
+            }
+            None => Vec::new(),
+        };
+
+        segments.push(TranscriptionSegment {
+            t0,
+            t1,
+            text: segment_text.trim().to_string(),
+            no_speech_probability: segment.no_speech_probability(),
+            words,
+        });
+    }
+    Ok(segments)
+}
+
+/// A decoded text token with its timestamp in milliseconds
+struct TokenTiming {
+    text: String,
+    t0: i64,
+    t1: i64,
+    probability: f32,
+}
+
+/// Join BPE tokens into words: a token starting with a space begins a new
+/// word, anything else (word pieces, punctuation) extends the current one.
+/// Timings are kept inside the segment and words never overlap.
+fn group_words(tokens: &[TokenTiming], segment_t0: i64, segment_t1: i64) -> Vec<TranscriptionWord> {
+    let mut words: Vec<TranscriptionWord> = Vec::new();
+    let mut token_count = 0;
+
+    for token in tokens {
+        if token.text.trim().is_empty() {
+            continue;
         }
+        let t0 = token.t0.clamp(segment_t0, segment_t1);
+        let t1 = token.t1.clamp(t0, segment_t1);
 
-        // Emit progress: Complete
-        if let Some(app) = &app_handle {
-            let _ = app.emit("transcription-progress", TranscriptionProgress {
-                stage: "complete".to_string(),
-                progress: 1.0,
-            });
+        match words.last_mut() {
+            Some(word) if !token.text.starts_with(char::is_whitespace) => {
+                word.text.push_str(&token.text);
+                word.t1 = word.t1.max(t1);
+                // Running mean over the word's tokens
+                token_count += 1;
+                word.probability += (token.probability - word.probability) / token_count as f32;
+            }
+            previous => {
+                let t0 = previous.map_or(t0, |previous| t0.max(previous.t1));
+                words.push(TranscriptionWord {
+                    text: token.text.trim_start().to_string(),
+                    t0,
+                    t1: t1.max(t0),
+                    probability: token.probability,
+                });
+                token_count = 1;
+            }
         }
+    }
 
-        Ok(result.trim().to_string())
+    for word in words.iter_mut() {
+        word.text = word.text.trim_end().to_string();
+    }
+    words
+}
+
+fn join_segment_text(segments: &[TranscriptionSegment]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const SAMPLE_RATE: usize = 16_000;
+const STREAM_STEP_MS: usize = 1_000;    // New audio required before re-running inference
+const STREAM_WINDOW_MS: usize = 10_000; // Window length after which stable segments are committed
+const STREAM_MAX_WINDOW_MS: usize = 30_000; // Whisper's maximum context; everything is committed past this
+const STREAM_DETECT_MS: usize = 3_000;  // Audio required before auto-detecting the language
+
+/// Payload of the `transcription-partial` event emitted by streaming sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionPartial {
+    pub session_id: String,
+    pub committed_text: String, // Text that will no longer change
+    pub tentative_text: String, // Text of the current window, may be revised
+    pub is_final: bool,
+}
+
+/// Incremental transcription over a sliding window of pushed audio.
+///
+/// Audio is buffered until at least `STREAM_STEP_MS` of new samples arrived,
+/// then the whole window is re-transcribed. Once the window grows past
+/// `STREAM_WINDOW_MS`, every segment except the last one is committed and the
+/// buffer is trimmed to the start of the last segment.
+pub struct StreamingSession {
+    variant: ModelVariant,
+    options: TranscriptionOptions,
+    language: Option<(String, Option<f32>)>,
+    buffer: Vec<f32>,
+    buffer_offset_ms: i64,
+    samples_since_inference: usize,
+    committed: Vec<TranscriptionSegment>,
+    tentative: Vec<TranscriptionSegment>,
+}
+
+impl StreamingSession {
+    pub fn new(variant: ModelVariant, options: TranscriptionOptions) -> Result<Self, String> {
+        options.validate()?;
+        let options = options.for_model(&variant)?;
+
+        Ok(StreamingSession {
+            variant,
+            options,
+            language: None,
+            buffer: Vec::new(),
+            buffer_offset_ms: 0,
+            samples_since_inference: 0,
+            committed: Vec::new(),
+            tentative: Vec::new(),
+        })
+    }
+
+    /// Append audio to the window. Returns true if enough audio arrived for
+    /// `step` to re-run inference.
+    pub fn append(&mut self, chunk: &[f32]) -> bool {
+        self.buffer.extend_from_slice(chunk);
+        self.samples_since_inference += chunk.len();
+
+        if self.samples_since_inference < SAMPLE_RATE * STREAM_STEP_MS / 1000 {
+            return false;
+        }
+
+        // Language detection is unreliable on very short audio
+        !(self.language.is_none()
+            && self.options.is_auto_language()
+            && self.buffer.len() < SAMPLE_RATE * STREAM_DETECT_MS / 1000)
+    }
+
+    /// Re-transcribe the window and commit the segments that became stable
+    pub fn step(&mut self, ctx: &WhisperContext, n_threads: usize) -> Result<(), String> {
+        self.infer(ctx, n_threads)?;
+
+        if self.buffer.len() >= SAMPLE_RATE * STREAM_MAX_WINDOW_MS / 1000 {
+            self.commit_all();
+        } else if self.buffer.len() >= SAMPLE_RATE * STREAM_WINDOW_MS / 1000 {
+            self.commit_stable();
+        }
+
+        Ok(())
+    }
+
+    /// Transcribe whatever is left in the window and commit it
+    pub fn finish(mut self, ctx: &WhisperContext, n_threads: usize) -> Result<TranscriptionResult, String> {
+        if self.samples_since_inference > 0 && !self.buffer.is_empty() {
+            self.infer(ctx, n_threads)?;
+        }
+        self.commit_all();
+
+        let (language, language_probability) = self.language
+            .unwrap_or_else(|| (self.options.language.to_lowercase(), None));
+
+        Ok(TranscriptionResult {
+            text: join_segment_text(&self.committed),
+            segments: self.committed,
+            language,
+            language_probability,
+        })
     }
 
     pub fn variant(&self) -> &ModelVariant {
         &self.variant
     }
+
+    pub fn partial(&self, session_id: &str) -> TranscriptionPartial {
+        TranscriptionPartial {
+            session_id: session_id.to_string(),
+            committed_text: join_segment_text(&self.committed),
+            tentative_text: join_segment_text(&self.tentative),
+            is_final: false,
+        }
+    }
+
+    fn infer(&mut self, ctx: &WhisperContext, n_threads: usize) -> Result<(), String> {
+        self.samples_since_inference = 0;
+
+        // Language detection needs a state, so it runs once on the first window
+        if self.language.is_none() {
+            let mut state = ctx.ctx.create_state()
+                .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
+            self.language = Some(resolve_language(
+                &mut state,
+                &self.buffer,
+                &self.options,
+                n_threads,
+            )?);
+        }
+
+        let language = self.language.as_ref().map(|(code, _)| code.as_str()).unwrap_or("en");
+        let mut segments = ctx.transcribe_window(&self.buffer, language, &self.options, n_threads)?;
+
+        for segment in segments.iter_mut() {
+            segment.shift(self.buffer_offset_ms);
+        }
+        self.tentative = segments;
+
+        Ok(())
+    }
+
+    /// Commit every segment but the last and drop the audio they covered
+    fn commit_stable(&mut self) {
+        if self.tentative.len() < 2 {
+            return;
+        }
+
+        let last = self.tentative.pop().unwrap();
+        let last_start = last.t0;
+        self.committed.append(&mut self.tentative);
+        self.tentative.push(last);
+        self.trim_buffer_to(last_start);
+    }
+
+    fn commit_all(&mut self) {
+        self.committed.append(&mut self.tentative);
+        self.buffer_offset_ms += (self.buffer.len() * 1000 / SAMPLE_RATE) as i64;
+        self.buffer.clear();
+    }
+
+    fn trim_buffer_to(&mut self, timeline_ms: i64) {
+        let drop_ms = (timeline_ms - self.buffer_offset_ms).max(0);
+        let drop_samples = (drop_ms as usize * SAMPLE_RATE / 1000).min(self.buffer.len());
+
+        self.buffer.drain(..drop_samples);
+        self.buffer_offset_ms += (drop_samples * 1000 / SAMPLE_RATE) as i64;
+    }
+}
+
+/// Run whisper's language detector on the first 30 seconds of audio.
+/// Returns the most likely language code and its probability.
+fn detect_language(
+    state: &mut WhisperState,
+    audio_data: &[f32],
+    n_threads: usize,
+) -> Result<(String, f32), String> {
+    state.pcm_to_mel(audio_data, n_threads)
+        .map_err(|e| format!("Failed to compute spectrogram for language detection: {}", e))?;
+
+    let (lang_id, probabilities) = state.lang_detect(0, n_threads)
+        .map_err(|e| format!("Language detection failed: {}", e))?;
+
+    let language = whisper_rs::get_lang_str(lang_id)
+        .ok_or_else(|| format!("Unknown language id detected: {}", lang_id))?;
+    let probability = probabilities.get(lang_id as usize).copied().unwrap_or(0.0);
+
+    Ok((language.to_string(), probability))
+}
+
+/// Error returned when a run was aborted through its cancel flag
+pub const TRANSCRIPTION_CANCELLED: &str = "Transcription cancelled";
+
+// Each session has its own lock so inference on one does not block the others.
+// The slot is emptied once the session is finished.
+type SharedSession = Arc<tokio::sync::Mutex<Option<StreamingSession>>>;
+
+/// Sessions nobody pushed to or finished for this long are dropped
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+struct SessionEntry {
+    session: SharedSession,
+    last_used: Instant,
 }
 
-// Global state to hold the loaded model
+// Streaming sessions by ID
 lazy_static::lazy_static! {
-    static ref WHISPER_MODEL: Arc<Mutex<Option<WhisperContext>>> = Arc::new(Mutex::new(None));
+    static ref STREAMING_SESSIONS: Arc<Mutex<HashMap<String, SessionEntry>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+}
+
+/// Drop sessions that have been idle past `STREAM_IDLE_TIMEOUT`, e.g. because
+/// the client went away without finishing them. A push still running on a
+/// dropped session keeps its own handle and completes normally.
+fn reap_idle_sessions(sessions: &mut HashMap<String, SessionEntry>, now: Instant) {
+    sessions.retain(|session_id, entry| {
+        let active = now.saturating_duration_since(entry.last_used) < STREAM_IDLE_TIMEOUT;
+        if !active {
+            eprintln!("Dropping idle transcription session {}", session_id);
+        }
+        active
+    });
+}
+
+fn streaming_session(session_id: &str) -> Result<SharedSession, String> {
+    let now = Instant::now();
+    let mut sessions = STREAMING_SESSIONS.lock().unwrap();
+    reap_idle_sessions(&mut sessions, now);
+
+    let entry = sessions
+        .get_mut(session_id)
+        .ok_or_else(|| format!("Unknown transcription session: {}", session_id))?;
+    entry.last_used = now;
+    Ok(entry.session.clone())
 }
 
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
 #[tauri::command]
-pub async fn load_whisper_model(
-    path: String,
+pub async fn transcribe_audio(
+    audio_data: Vec<f32>,
     variant: ModelVariant,
-) -> Result<(), String> {
-    let model_path = PathBuf::from(path);
-    
-    if !model_path.exists() {
-        return Err(format!("Model file not found: {:?}", model_path));
-    }
+    options: Option<TranscriptionOptions>,
+    format: Option<AudioFormat>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    let audio_data = condition_audio(&audio_data, &format.unwrap_or_default())?;
+    transcribe_queued(audio_data, variant, options, TranscriptionPriority::Normal, app_handle)
+        .await
+        .map(|result| result.text)
+}
 
-    let context = WhisperContext::new(model_path, variant)?;
-    
-    let mut model = WHISPER_MODEL.lock().unwrap();
-    *model = Some(context);
-    
-    Ok(())
+#[tauri::command]
+pub async fn transcribe_audio_segments(
+    audio_data: Vec<f32>,
+    variant: ModelVariant,
+    options: Option<TranscriptionOptions>,
+    format: Option<AudioFormat>,
+    app_handle: AppHandle,
+) -> Result<TranscriptionResult, String> {
+    let options = options.unwrap_or_default();
+    let audio_data = condition_audio(&audio_data, &format.unwrap_or_default())?;
+    transcribe_queued(audio_data, variant, options, TranscriptionPriority::Normal, app_handle).await
 }
 
+/// Start a streaming session. Without a variant the session uses the most
+/// recently used model, resolved now so it does not change mid-session.
 #[tauri::command]
-pub async fn unload_whisper_model() -> Result<(), String> {
-    let mut model = WHISPER_MODEL.lock().unwrap();
-    *model = None;
+pub async fn start_transcription_session(
+    variant: Option<ModelVariant>,
+    options: Option<TranscriptionOptions>,
+) -> Result<String, String> {
+    let variant = match variant {
+        Some(variant) => variant,
+        None => get_model(None)?.variant().clone(),
+    };
+    let session = StreamingSession::new(variant, options.unwrap_or_default())?;
+    let session_id = format!("session-{}", NEXT_SESSION_ID.fetch_add(1, Ordering::SeqCst));
+
+    let now = Instant::now();
+    let mut sessions = STREAMING_SESSIONS.lock().unwrap();
+    reap_idle_sessions(&mut sessions, now);
+    sessions.insert(session_id.clone(), SessionEntry {
+        session: Arc::new(tokio::sync::Mutex::new(Some(session))),
+        last_used: now,
+    });
+
+    Ok(session_id)
+}
+
+/// Buffer audio for a session. Once enough arrived, inference runs on a
+/// blocking worker after waiting for a slot in the transcription queue.
+#[tauri::command]
+pub async fn push_transcription_audio(
+    session_id: String,
+    audio_chunk: Vec<f32>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let session = streaming_session(&session_id)?;
+    // Pushes to the same session are applied in order
+    let mut session = session.lock_owned().await;
+    let variant = {
+        let state = session
+            .as_mut()
+            .ok_or_else(|| format!("Transcription session {} has finished", session_id))?;
+        if !state.append(&audio_chunk) {
+            return Ok(());
+        }
+        state.variant().clone()
+    };
+
+    let slot = acquire_slot(variant, TranscriptionPriority::Normal, app_handle.clone()).await?;
+    let worker_app = app_handle.clone();
+    let partial = tauri::async_runtime::spawn_blocking(move || {
+        let state = session.as_mut().ok_or("Transcription session has finished")?;
+        let ctx = acquire_model(state.variant(), &worker_app)?;
+        state.step(&ctx, slot.n_threads)?;
+        Ok::<_, String>(state.partial(&session_id))
+    })
+    .await
+    .map_err(|e| format!("Streaming transcription task failed: {}", e))??;
+
+    let _ = app_handle.emit("transcription-partial", partial);
+
     Ok(())
 }
 
 #[tauri::command]
-pub async fn transcribe_audio(
-    audio_data: Vec<f32>,
-    _variant: ModelVariant,
+pub async fn finish_transcription_session(
+    session_id: String,
     app_handle: AppHandle,
-) -> Result<String, String> {
-    let model = WHISPER_MODEL.lock().unwrap();
-    
-    match model.as_ref() {
-        Some(ctx) => ctx.transcribe(audio_data, Some(app_handle)),
-        None => Err("No Whisper model loaded".to_string()),
-    }
+) -> Result<TranscriptionResult, String> {
+    let session = STREAMING_SESSIONS
+        .lock()
+        .unwrap()
+        .remove(&session_id)
+        .ok_or_else(|| format!("Unknown transcription session: {}", session_id))?;
+
+    // Waits for an in-flight push to finish first
+    let session = session
+        .session
+        .lock()
+        .await
+        .take()
+        .ok_or_else(|| format!("Transcription session {} has finished", session_id))?;
+
+    let slot = acquire_slot(session.variant().clone(), TranscriptionPriority::Normal, app_handle.clone()).await?;
+    let worker_app = app_handle.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let ctx = acquire_model(session.variant(), &worker_app)?;
+        session.finish(&ctx, slot.n_threads)
+    })
+    .await
+    .map_err(|e| format!("Streaming transcription task failed: {}", e))??;
+
+    let _ = app_handle.emit("transcription-partial", TranscriptionPartial {
+        session_id,
+        committed_text: result.text.clone(),
+        tentative_text: String::new(),
+        is_final: true,
+    });
+
+    Ok(result)
 }
 
+/// Discard a streaming session without transcribing the rest of its audio
 #[tauri::command]
-pub async fn get_whisper_model_status() -> Result<Option<ModelVariant>, String> {
-    let model = WHISPER_MODEL.lock().unwrap();
-    Ok(model.as_ref().map(|ctx| ctx.variant().clone()))
+pub async fn cancel_transcription_session(session_id: String) -> Result<(), String> {
+    let entry = STREAMING_SESSIONS
+        .lock()
+        .unwrap()
+        .remove(&session_id)
+        .ok_or_else(|| format!("Unknown transcription session: {}", session_id))?;
+
+    // Waits for an in-flight push to finish first
+    entry.session.lock().await.take();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(t0: i64, t1: i64, text: &str) -> TranscriptionSegment {
+        TranscriptionSegment {
+            t0,
+            t1,
+            text: text.to_string(),
+            no_speech_probability: 0.0,
+            words: Vec::new(),
+        }
+    }
+
+    fn session(buffer_ms: usize) -> StreamingSession {
+        StreamingSession {
+            variant: ModelVariant::Base,
+            options: TranscriptionOptions::default(),
+            language: Some(("en".to_string(), None)),
+            buffer: vec![0.0; SAMPLE_RATE * buffer_ms / 1000],
+            buffer_offset_ms: 0,
+            samples_since_inference: 0,
+            committed: Vec::new(),
+            tentative: Vec::new(),
+        }
+    }
+
+    fn token(text: &str, t0: i64, t1: i64, probability: f32) -> TokenTiming {
+        TokenTiming {
+            text: text.to_string(),
+            t0,
+            t1,
+            probability,
+        }
+    }
+
+    #[test]
+    fn custom_variants_need_the_custom_prefix() {
+        let variant: ModelVariant = serde_json::from_str("\"base.en\"").unwrap();
+        assert_eq!(variant, ModelVariant::BaseEn);
+
+        let variant: ModelVariant = serde_json::from_str("\"custom-whisper-nl\"").unwrap();
+        assert_eq!(variant, ModelVariant::Custom("custom-whisper-nl".to_string()));
+        assert_eq!(serde_json::to_string(&variant).unwrap(), "\"custom-whisper-nl\"");
+
+        assert!(serde_json::from_str::<ModelVariant>("\"base-en\"").is_err());
+    }
+
+    #[test]
+    fn tokens_are_grouped_into_words() {
+        let tokens = vec![
+            token(" Hello", 0, 400, 0.9),
+            token(" wor", 400, 600, 0.8),
+            token("ld", 600, 800, 0.6),
+            token(",", 800, 900, 1.0),
+            token(" ", 900, 950, 0.1),
+        ];
+
+        let words = group_words(&tokens, 0, 1000);
+
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].text, "Hello");
+        assert_eq!((words[0].t0, words[0].t1), (0, 400));
+        assert_eq!(words[1].text, "world,");
+        assert_eq!((words[1].t0, words[1].t1), (400, 900));
+        // Mean of the word's three tokens; the whitespace token is skipped
+        assert!((words[1].probability - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn word_timings_stay_inside_segment_and_do_not_overlap() {
+        let tokens = vec![
+            token(" One", 900, 1600, 0.5),
+            token(" two", 1400, 2600, 0.5),
+        ];
+
+        let words = group_words(&tokens, 1000, 2500);
+
+        assert_eq!((words[0].t0, words[0].t1), (1000, 1600));
+        assert_eq!((words[1].t0, words[1].t1), (1600, 2500));
+    }
+
+    #[test]
+    fn explicit_decoding_fields_override_the_preset() {
+        let options: TranscriptionOptions = serde_json::from_str(
+            r#"{"preset": "accurate", "decoding": {"temperature_increment": 0.0}}"#,
+        )
+        .unwrap();
+        let accurate = DecodingConfig::preset(DecodingPreset::Accurate);
+        assert_eq!(options.decoding.strategy, accurate.strategy);
+        assert_eq!(options.decoding.temperature_increment, 0.0);
+        assert_eq!(options.decoding.entropy_threshold, accurate.entropy_threshold);
+
+        // Without a preset, fields override the balanced defaults
+        let options: TranscriptionOptions =
+            serde_json::from_str(r#"{"decoding": {"no_speech_threshold": 0.3}}"#).unwrap();
+        let balanced = DecodingConfig::default();
+        assert_eq!(options.decoding.strategy, balanced.strategy);
+        assert_eq!(options.decoding.no_speech_threshold, 0.3);
+
+        // The resolved config survives a round trip through settings files
+        let saved = serde_json::to_string(&options).unwrap();
+        let loaded: TranscriptionOptions = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded.decoding.no_speech_threshold, 0.3);
+
+        assert!(serde_json::from_str::<TranscriptionOptions>(r#"{"preset": "slowest"}"#).is_err());
+    }
+
+    #[test]
+    fn segment_text_is_joined_without_empty_segments() {
+        let segments = vec![
+            segment(0, 1000, "Hello"),
+            segment(1000, 1500, ""),
+            segment(1500, 2000, "world."),
+        ];
+        assert_eq!(join_segment_text(&segments), "Hello world.");
+        assert_eq!(join_segment_text(&[]), "");
+    }
+
+    #[test]
+    fn commit_stable_keeps_last_segment_and_trims_buffer() {
+        let mut session = session(12_000);
+        session.tentative = vec![
+            segment(0, 4000, "one"),
+            segment(4000, 9000, "two"),
+            segment(9000, 12_000, "three"),
+        ];
+
+        session.commit_stable();
+
+        assert_eq!(join_segment_text(&session.committed), "one two");
+        assert_eq!(join_segment_text(&session.tentative), "three");
+        assert_eq!(session.buffer_offset_ms, 9000);
+        assert_eq!(session.buffer.len(), SAMPLE_RATE * 3);
+    }
+
+    #[test]
+    fn commit_stable_waits_for_a_second_segment() {
+        let mut session = session(12_000);
+        session.tentative = vec![segment(0, 12_000, "one long sentence")];
+
+        session.commit_stable();
+
+        assert!(session.committed.is_empty());
+        assert_eq!(session.tentative.len(), 1);
+        assert_eq!(session.buffer.len(), SAMPLE_RATE * 12);
+    }
+
+    #[test]
+    fn commit_all_moves_the_window_past_the_buffer() {
+        let mut session = session(5000);
+        session.buffer_offset_ms = 9000;
+        session.tentative = vec![segment(9000, 14_000, "rest")];
+
+        session.commit_all();
+
+        assert_eq!(join_segment_text(&session.committed), "rest");
+        assert!(session.tentative.is_empty());
+        assert!(session.buffer.is_empty());
+        assert_eq!(session.buffer_offset_ms, 14_000);
+    }
+
+    #[test]
+    fn trim_is_relative_to_buffer_offset_and_bounded() {
+        let mut session = session(4000);
+        session.buffer_offset_ms = 10_000;
+
+        // Before the window: nothing to drop
+        session.trim_buffer_to(8000);
+        assert_eq!(session.buffer.len(), SAMPLE_RATE * 4);
+
+        session.trim_buffer_to(11_500);
+        assert_eq!(session.buffer_offset_ms, 11_500);
+        assert_eq!(session.buffer.len(), SAMPLE_RATE * 5 / 2);
+
+        // Past the end: the buffer is emptied, not over-drained
+        session.trim_buffer_to(60_000);
+        assert!(session.buffer.is_empty());
+        assert_eq!(session.buffer_offset_ms, 14_000);
+    }
+
+    #[test]
+    fn idle_sessions_are_reaped() {
+        let now = Instant::now();
+        let entry = |idle: Duration| SessionEntry {
+            session: Arc::new(tokio::sync::Mutex::new(Some(session(0)))),
+            last_used: now.checked_sub(idle).unwrap_or(now),
+        };
+        let mut sessions = HashMap::new();
+        sessions.insert("active".to_string(), entry(Duration::from_secs(10)));
+        sessions.insert("idle".to_string(), entry(STREAM_IDLE_TIMEOUT + Duration::from_secs(1)));
+
+        reap_idle_sessions(&mut sessions, now);
+
+        assert!(sessions.contains_key("active"));
+        assert!(!sessions.contains_key("idle"));
+    }
 }
@@ -39,7 +39,7 @@ export function ModelSelection({ settings, onSettingsChange }: ModelSelectionPro
     try {
       await modelManager.initialize();
       const statuses = new Map<ModelVariant, boolean>();
-      const variants: ModelVariant[] = ['tiny', 'base', 'small', 'medium', 'large'];
+      const variants = modelManager.getAllModelMetadata().map((metadata) => metadata.variant);
       
       for (const variant of variants) {
         const downloaded = await modelManager.isModelDownloaded(variant);
@@ -195,6 +195,25 @@ describe('AppContext', () => {
     expect(result.current.state.isHistoryOpen).toBe(false);
   });
 
+  it('handles SET_DICTATING action without touching the recording workflow', () => {
+    const { result } = renderHook(() => useAppContext(), { wrapper });
+
+    expect(result.current.state.isDictating).toBe(false);
+
+    act(() => {
+      result.current.dispatch({ type: 'SET_DICTATING', payload: true });
+    });
+
+    expect(result.current.state.isDictating).toBe(true);
+    expect(result.current.state.recordingState).toBe('idle');
+
+    act(() => {
+      result.current.dispatch({ type: 'SET_DICTATING', payload: false });
+    });
+
+    expect(result.current.state.isDictating).toBe(false);
+  });
+
   it('handles SET_ERROR action', () => {
     const { result } = renderHook(() => useAppContext(), { wrapper });
 
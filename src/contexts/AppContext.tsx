@@ -41,6 +41,9 @@ export interface AppState {
     enrichment: EnrichmentResult | null;
   };
   
+  // Native hotkey dictation, recorded by the backend independently of the workflow above
+  isDictating: boolean;
+
   // UI state
   selectedRecordingId: string | null;
   isHistoryOpen: boolean;
@@ -82,6 +85,7 @@ export type AppAction =
   | { type: 'ENRICHMENT_COMPLETE'; payload: EnrichmentResult }
   | { type: 'RECORDING_COMPLETE' }
   | { type: 'RESET_RECORDING' }
+  | { type: 'SET_DICTATING'; payload: boolean }
   
   // UI actions
   | { type: 'SELECT_RECORDING'; payload: string | null }
@@ -109,6 +113,7 @@ const initialState: AppState = {
     transcription: null,
     enrichment: null,
   },
+  isDictating: false,
   selectedRecordingId: null,
   isHistoryOpen: false,
   theme: 'system',
@@ -249,6 +254,12 @@ function appReducer(state: AppState, action: AppAction): AppState {
           error: null,
         };
 
+      case 'SET_DICTATING':
+        return {
+          ...state,
+          isDictating: action.payload,
+        };
+
       case 'SELECT_RECORDING':
         return {
           ...state,
@@ -377,7 +388,7 @@ export function AppProvider({ children, initialState: customInitialState }: AppP
     customInitialState ? { ...initialState, ...customInitialState } : initialState
   );
 
-  // Initialize Tauri service and listen for global shortcuts
+  // Initialize Tauri service and follow native dictation state
   useEffect(() => {
     const isDesktop = tauriService.isRunningInDesktop();
     
@@ -410,16 +421,10 @@ export function AppProvider({ children, initialState: customInitialState }: AppP
           console.error('Error validating models on startup:', error);
         });
       
-      // Listen for global shortcut events
-      const unsubscribe = tauriService.onGlobalShortcut(() => {
-        // Toggle recording when global shortcut is pressed
-        if (state.recordingState === 'idle') {
-          dispatch({ type: 'START_RECORDING' });
-        } else if (state.recordingState === 'recording') {
-          // This will be handled by the recording component
-          // Just emit an event that components can listen to
-          window.dispatchEvent(new CustomEvent('stop-recording-requested'));
-        }
+      // The backend records hotkey dictation itself; only mirror its state so
+      // the webview recorder never opens the microphone a second time
+      const unsubscribe = tauriService.onDictationState(({ recording }) => {
+        dispatch({ type: 'SET_DICTATING', payload: recording });
       });
       
       return () => {
@@ -427,7 +432,7 @@ export function AppProvider({ children, initialState: customInitialState }: AppP
         tauriService.cleanup();
       };
     }
-  }, [state.recordingState]);
+  }, []);
 
   // Load settings and theme from localStorage on mount
   useEffect(() => {
@@ -6,6 +6,7 @@
 
 import { useEffect, useState, useCallback } from 'react';
 import { tauriService } from '@/services/tauri';
+import type { DictationState } from '@/services/tauri';
 
 export function useTauri() {
   const [isDesktop, setIsDesktop] = useState(false);
@@ -70,10 +71,10 @@ export function useTauri() {
     }
   }, [isDesktop]);
 
-  const onGlobalShortcut = useCallback(
-    (callback: () => void) => {
+  const onDictationState = useCallback(
+    (callback: (state: DictationState) => void) => {
       if (!isDesktop) return () => {};
-      return tauriService.onGlobalShortcut(callback);
+      return tauriService.onDictationState(callback);
     },
     [isDesktop]
   );
@@ -85,6 +86,6 @@ export function useTauri() {
     showWindow,
     hideWindow,
     focusWindow,
-    onGlobalShortcut,
+    onDictationState,
   };
 }
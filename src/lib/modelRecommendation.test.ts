@@ -25,41 +25,51 @@ jest.mock('./resourceMonitoring', () => ({
 const mockModelMetadata: ModelMetadata[] = [
   {
     variant: 'tiny',
+    filename: 'ggml-tiny.bin',
     size: 75 * 1024 * 1024,
     checksum: 'test',
     downloadUrl: 'test',
+    memoryRequirement: 390 * 1024 * 1024,
     accuracy: 'good',
     estimatedSpeed: 'fast',
   },
   {
     variant: 'base',
+    filename: 'ggml-base.bin',
     size: 142 * 1024 * 1024,
     checksum: 'test',
     downloadUrl: 'test',
+    memoryRequirement: 500 * 1024 * 1024,
     accuracy: 'better',
     estimatedSpeed: 'fast',
   },
   {
     variant: 'small',
+    filename: 'ggml-small.bin',
     size: 466 * 1024 * 1024,
     checksum: 'test',
     downloadUrl: 'test',
+    memoryRequirement: 1000 * 1024 * 1024,
     accuracy: 'better',
     estimatedSpeed: 'medium',
   },
   {
     variant: 'medium',
+    filename: 'ggml-medium.bin',
     size: 1.5 * 1024 * 1024 * 1024,
     checksum: 'test',
     downloadUrl: 'test',
+    memoryRequirement: 2600 * 1024 * 1024,
     accuracy: 'best',
     estimatedSpeed: 'slow',
   },
   {
     variant: 'large',
+    filename: 'ggml-large-v3.bin',
     size: 2.9 * 1024 * 1024 * 1024,
     checksum: 'test',
     downloadUrl: 'test',
+    memoryRequirement: 4700 * 1024 * 1024,
     accuracy: 'best',
     estimatedSpeed: 'slow',
   },
@@ -173,6 +183,24 @@ describe('modelRecommendation', () => {
       expect(allRecommended).not.toContain('medium');
       expect(allRecommended).not.toContain('large');
     });
+
+    it('should only recommend variants whose memory requirement fits', async () => {
+      const { getSystemMemory } = require('./resourceMonitoring');
+      getSystemMemory.mockResolvedValue({
+        total: 8 * 1024 * 1024 * 1024,
+        available: 2.2 * 1024 * 1024 * 1024, // Low, below the medium model's requirement
+        used: 5.8 * 1024 * 1024 * 1024,
+        free: 2.2 * 1024 * 1024 * 1024,
+      });
+
+      const recommendation = await recommendModelVariant(
+        10 * 1024 * 1024 * 1024,
+        mockModelMetadata
+      );
+
+      expect(recommendation.recommendedVariant).toBe('tiny');
+      expect(recommendation.alternativeVariants).toEqual(['base', 'small']);
+    });
   });
 
   describe('isVariantRecommended', () => {
@@ -215,11 +243,15 @@ describe('modelRecommendation', () => {
 
   describe('getModelMemoryRequirement', () => {
     it('should return memory requirement for each variant', () => {
-      expect(getModelMemoryRequirement('tiny')).toBe(500 * 1024 * 1024);
-      expect(getModelMemoryRequirement('base')).toBe(800 * 1024 * 1024);
-      expect(getModelMemoryRequirement('small')).toBe(1.5 * 1024 * 1024 * 1024);
-      expect(getModelMemoryRequirement('medium')).toBe(3 * 1024 * 1024 * 1024);
-      expect(getModelMemoryRequirement('large')).toBe(5 * 1024 * 1024 * 1024);
+      expect(getModelMemoryRequirement('tiny', mockModelMetadata)).toBe(390 * 1024 * 1024);
+      expect(getModelMemoryRequirement('base', mockModelMetadata)).toBe(500 * 1024 * 1024);
+      expect(getModelMemoryRequirement('small', mockModelMetadata)).toBe(1000 * 1024 * 1024);
+      expect(getModelMemoryRequirement('medium', mockModelMetadata)).toBe(2600 * 1024 * 1024);
+      expect(getModelMemoryRequirement('large', mockModelMetadata)).toBe(4700 * 1024 * 1024);
+    });
+
+    it('should return undefined for variants missing from the metadata', () => {
+      expect(getModelMemoryRequirement('large-v3-turbo', mockModelMetadata)).toBeUndefined();
     });
   });
 });
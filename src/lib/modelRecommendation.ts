@@ -13,18 +13,6 @@ export interface ModelRecommendation {
   diskSpaceAvailable: number;
 }
 
-/**
- * Memory requirements for each model variant (estimated)
- * These are approximate values based on model size + overhead
- */
-const MODEL_MEMORY_REQUIREMENTS: Record<ModelVariant, number> = {
-  tiny: 500 * 1024 * 1024,      // ~500 MB
-  base: 800 * 1024 * 1024,      // ~800 MB
-  small: 1.5 * 1024 * 1024 * 1024,  // ~1.5 GB
-  medium: 3 * 1024 * 1024 * 1024,   // ~3 GB
-  large: 5 * 1024 * 1024 * 1024,    // ~5 GB
-};
-
 /**
  * Model variants ordered by size (smallest to largest)
  */
@@ -127,11 +115,11 @@ function findSuitableVariants(
   const suitable: ModelVariant[] = [];
 
   for (const variant of MODEL_VARIANTS_BY_SIZE) {
-    const memoryRequired = MODEL_MEMORY_REQUIREMENTS[variant];
     const metadata = modelMetadata.find(m => m.variant === variant);
 
     if (!metadata) continue;
 
+    const memoryRequired = metadata.memoryRequirement;
     const diskRequired = metadata.size;
 
     // Check if variant fits in both memory and disk
@@ -239,8 +227,12 @@ export async function isVariantRecommended(
 /**
  * Get memory requirement for a specific model variant
  * @param variant - Model variant
- * @returns Estimated memory requirement in bytes
+ * @param modelMetadata - Array of model metadata, as loaded from the backend catalog
+ * @returns Estimated memory requirement in bytes, or undefined for unknown variants
  */
-export function getModelMemoryRequirement(variant: ModelVariant): number {
-  return MODEL_MEMORY_REQUIREMENTS[variant];
+export function getModelMemoryRequirement(
+  variant: ModelVariant,
+  modelMetadata: ModelMetadata[]
+): number | undefined {
+  return modelMetadata.find(m => m.variant === variant)?.memoryRequirement;
 }
@@ -158,6 +158,7 @@ describe('calculateStateDiff', () => {
         transcription: null,
         enrichment: null,
       },
+      isDictating: false,
       selectedRecordingId: null,
       isHistoryOpen: false,
       error: null,
@@ -187,6 +188,7 @@ describe('calculateStateDiff', () => {
         transcription: null,
         enrichment: null,
       },
+      isDictating: false,
       selectedRecordingId: null,
       isHistoryOpen: false,
       error: null,
@@ -211,6 +213,7 @@ describe('visualizeState', () => {
         transcription: null,
         enrichment: null,
       },
+      isDictating: false,
       selectedRecordingId: null,
       isHistoryOpen: false,
       error: null,
@@ -235,6 +238,7 @@ describe('visualizeState', () => {
         transcription: null,
         enrichment: null,
       },
+      isDictating: false,
       selectedRecordingId: null,
       isHistoryOpen: false,
       error: {
@@ -8,6 +8,7 @@
 import { invoke } from '@tauri-apps/api/core';
 import { listen, emit } from '@tauri-apps/api/event';
 import { getCurrentWindow } from '@tauri-apps/api/window';
+import type { DictationState } from './types';
 
 export interface TauriServiceConfig {
   enableSystemTray?: boolean;
@@ -57,12 +58,12 @@ export class TauriService {
     }
 
     try {
-      // Listen for toggle recording events from backend
-      const unlistenToggle = await listen('toggle-recording', () => {
+      // Hotkey dictation is recorded natively; forward its state so the UI can reflect it
+      const unlistenDictation = await listen<DictationState>('dictation-state', (event) => {
         // Emit custom event that components can listen to
-        window.dispatchEvent(new CustomEvent('tauri:toggle-recording'));
+        window.dispatchEvent(new CustomEvent('tauri:dictation-state', { detail: event.payload }));
       });
-      this.listeners.push(unlistenToggle);
+      this.listeners.push(unlistenDictation);
 
       console.log('Tauri service initialized');
     } catch (error) {
@@ -179,14 +180,14 @@ export class TauriService {
   }
 
   /**
-   * Listen for global shortcut events
+   * Listen for native dictation starting and stopping (hotkey, tray or toggleRecording)
    */
-  onGlobalShortcut(callback: () => void): () => void {
-    const handler = () => callback();
-    window.addEventListener('tauri:toggle-recording', handler);
+  onDictationState(callback: (state: DictationState) => void): () => void {
+    const handler = (event: Event) => callback((event as CustomEvent<DictationState>).detail);
+    window.addEventListener('tauri:dictation-state', handler);
     
     return () => {
-      window.removeEventListener('tauri:toggle-recording', handler);
+      window.removeEventListener('tauri:dictation-state', handler);
     };
   }
 
@@ -11,4 +11,33 @@ export type {
   SystemTrayConfig,
   GlobalShortcutConfig,
   TauriCapabilities,
+  InputDeviceInfo,
+  AudioLevel,
+  CaptureStatus,
+  CapturedAudio,
+  DictationState,
+  DictationOutcome,
+  OutputMode,
+  OutputSettings,
+  OutputDelivery,
+  DictationSettings,
+  ShortcutAction,
+  ShortcutBinding,
+  ShortcutError,
+  ShortcutTriggered,
+  HistoryRecording,
+  NewRecording,
+  RecordingUpdate,
+  HistoryTranscript,
+  HistoryEnrichment,
+  RecordingDetail,
+  SearchHit,
+  ArchiveFormat,
+  RetentionPolicy,
+  ArchiveSettings,
+  ArchivedAudio,
+  CleanupReport,
+  TranscriptExportFormat,
+  TranscriptExportOptions,
+  TranscriptExportSummary,
 } from './types';
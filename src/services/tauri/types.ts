@@ -2,6 +2,13 @@
  * Tauri Service Types
  */
 
+import type {
+  LocalTranscriptionOptions,
+  ModelVariant,
+  SegmentedTranscription,
+  TranscriptionSegment,
+} from '../whisper/types';
+
 export interface TauriWindow {
   show: () => Promise<void>;
   hide: () => Promise<void>;
@@ -35,3 +42,272 @@ export interface TauriCapabilities {
   hasNotifications: boolean;
   hasFileSystem: boolean;
 }
+
+/**
+ * Input device reported by `list_input_devices`. File devices play a WAV
+ * file in real time and come from VOICE_INTELLIGENCE_FAKE_INPUT.
+ */
+export interface InputDeviceInfo {
+  id: string; // 'system:<name>' or 'file:<path>'
+  name: string;
+  kind: 'system' | 'file';
+  is_default: boolean;
+}
+
+/**
+ * Payload of the 'audio-level' event emitted during native capture
+ */
+export interface AudioLevel {
+  rms: number; // 0.0 to 1.0
+  peak: number; // 0.0 to 1.0
+  elapsed_ms: number;
+}
+
+/**
+ * Returned by `get_capture_status` and emitted as 'capture-state'
+ */
+export interface CaptureStatus {
+  capturing: boolean;
+  device_id: string | null; // null selects the system default
+  elapsed_ms: number | null;
+}
+
+/**
+ * Returned by `stop_capture`; the audio itself stays in the backend
+ */
+export interface CapturedAudio {
+  device_name: string;
+  duration_ms: number;
+}
+
+/**
+ * Payload of the 'dictation-state' event emitted when a native dictation
+ * recording starts or stops
+ */
+export interface DictationState {
+  recording: boolean;
+}
+
+/**
+ * Payload of the 'dictation-result' event emitted after a hotkey recording
+ */
+export interface DictationOutcome {
+  duration_ms: number;
+  result: SegmentedTranscription | null;
+  error: string | null;
+  output: OutputDelivery | null; // How the text reached the focused application
+  output_error: string | null;
+  recording_id: string | null; // History entry the transcript was saved to
+}
+
+/**
+ * Where transcribed text goes after a hotkey recording. Every mode places
+ * the text on the clipboard; paste and type then insert it into the focused app.
+ */
+export type OutputMode = 'clipboard' | 'paste' | 'type';
+
+/**
+ * Output sink settings (`get_output_settings` / `set_output_settings`),
+ * stored in output.json next to shortcuts.json
+ */
+export interface OutputSettings {
+  mode: OutputMode; // Default 'clipboard'
+  restore_clipboard: boolean; // Keep the previous clipboard text after pasting or typing
+  restore_delay_ms: number; // Default 300, at most 5000
+}
+
+/**
+ * Returned by `send_text_to_focused_app` and part of DictationOutcome
+ */
+export interface OutputDelivery {
+  mode: OutputMode; // Falls back to 'clipboard' while our window has focus
+  clipboard_restored: boolean; // A previous clipboard text was put back
+}
+
+/**
+ * Settings for hotkey recordings (`get_dictation_settings` / `set_dictation_settings`),
+ * stored in dictation.json next to shortcuts.json
+ */
+export interface DictationSettings {
+  variant: ModelVariant | null; // null uses the most recently used loaded model
+  options: LocalTranscriptionOptions;
+  min_hold_ms: number; // Push-to-talk presses shorter than this are discarded (default 300)
+}
+
+/**
+ * Actions that can be bound to global shortcuts in the backend
+ */
+export type ShortcutAction =
+  | 'toggle_recording'
+  | 'push_to_talk' // Records while held, transcribes on release
+  | 'cancel'
+  | 'paste_last_transcript'
+  | 'show_window';
+
+/**
+ * A binding returned by `list_shortcuts`, persisted to shortcuts.json in the app config dir
+ */
+export interface ShortcutBinding {
+  action: ShortcutAction;
+  accelerator: string | null; // e.g. 'CommandOrControl+Shift+Space'; null when unbound
+  registered: boolean; // Whether the OS accepted the registration
+}
+
+/**
+ * Error returned by the shortcut commands
+ */
+export type ShortcutError =
+  | { kind: 'invalid_accelerator'; accelerator: string; message: string }
+  | { kind: 'conflict'; accelerator: string; action: ShortcutAction } // Bound to another action
+  | { kind: 'unavailable'; accelerator: string; message: string } // Refused by the OS
+  | { kind: 'not_bound'; action: ShortcutAction }
+  | { kind: 'config'; message: string };
+
+/**
+ * Payload of the 'shortcut-triggered' event
+ */
+export interface ShortcutTriggered {
+  action: ShortcutAction;
+  pressed: boolean; // false for the release of a push-to-talk key
+}
+
+/**
+ * Recording stored in the backend history database (history.db in the app data dir)
+ */
+export interface HistoryRecording {
+  id: string;
+  created_at: number; // Unix time in milliseconds
+  updated_at: number;
+  title: string | null;
+  duration_ms: number | null;
+  starred: boolean;
+  tags: string[];
+  notes: string | null;
+  audio_format: ArchiveFormat | null; // null when no audio is archived
+  audio_size: number | null; // Archived audio size in bytes
+}
+
+/**
+ * Fields accepted by `create_recording`
+ */
+export interface NewRecording {
+  title?: string;
+  duration_ms?: number;
+  starred?: boolean;
+  tags?: string[];
+  notes?: string;
+}
+
+/**
+ * Partial update for `update_recording`; omitted fields are left unchanged,
+ * a null title or notes clears them
+ */
+export interface RecordingUpdate {
+  title?: string | null;
+  starred?: boolean;
+  tags?: string[];
+  notes?: string | null;
+}
+
+export interface HistoryTranscript {
+  id: number;
+  recording_id: string;
+  created_at: number;
+  text: string;
+  language: string | null;
+  model: string | null; // Model file that produced the transcript
+  segments: TranscriptionSegment[];
+}
+
+export interface HistoryEnrichment {
+  id: number;
+  recording_id: string;
+  created_at: number;
+  kind: string; // e.g. 'summary' or 'action_items'
+  content: string;
+  model: string | null;
+}
+
+/**
+ * Returned by `get_recording`; transcripts and enrichments are newest first
+ */
+export interface RecordingDetail {
+  recording: HistoryRecording;
+  transcripts: HistoryTranscript[];
+  enrichments: HistoryEnrichment[];
+}
+
+/**
+ * Returned by `search_transcripts`, best match first
+ */
+export interface SearchHit {
+  recording_id: string;
+  transcript_id: number;
+  snippet: string; // Matching text with hits wrapped in [ and ]
+  rank: number; // BM25 score, lower is better
+}
+
+/**
+ * Compression of archived recording audio, stored in the recordings directory
+ */
+export type ArchiveFormat = 'opus' | 'flac';
+
+/**
+ * Which archived audio the cleanup task removes; transcripts are kept
+ */
+export interface RetentionPolicy {
+  keep_days: number | null; // Default 30; null keeps audio forever
+  max_total_mb: number | null; // Default 1024; oldest audio is removed first
+  keep_starred: boolean; // Never remove audio of starred recordings
+}
+
+/**
+ * Audio archive settings (`get_archive_settings` / `set_archive_settings`),
+ * stored in archive.json next to shortcuts.json and loaded before the first cleanup
+ */
+export interface ArchiveSettings {
+  archive_dictations: boolean; // Store the audio of hotkey recordings
+  format: ArchiveFormat; // Default 'opus'
+  retention: RetentionPolicy;
+}
+
+/**
+ * Returned by `archive_audio_file`
+ */
+export interface ArchivedAudio {
+  recording_id: string;
+  format: ArchiveFormat;
+  size: number;
+  duration_ms: number;
+}
+
+/**
+ * Returned by `run_archive_cleanup` and emitted as 'archive-cleaned'
+ */
+export interface CleanupReport {
+  removed_files: number;
+  freed_bytes: number;
+  remaining_bytes: number;
+}
+
+/**
+ * Format of `export_transcript` / `export_history_transcript`
+ */
+export type TranscriptExportFormat = 'srt' | 'vtt' | 'txt' | 'json' | 'md';
+
+export interface TranscriptExportOptions {
+  max_line_length?: number; // Subtitle line length in characters (default 42)
+  max_lines?: number; // Lines per subtitle cue (default 2)
+  max_cue_duration_ms?: number; // Longer cues are split at word boundaries (default 7000)
+  include_timestamps?: boolean; // Prefix each segment with its start time in TXT and Markdown
+  title?: string; // Markdown heading
+}
+
+/**
+ * Returned by the transcript export commands
+ */
+export interface TranscriptExportSummary {
+  path: string;
+  bytes: number;
+  cues: number; // Subtitle cues, or segments for the other formats
+}
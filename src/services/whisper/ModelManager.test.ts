@@ -1,6 +1,6 @@
 import { describe, it, expect, jest, beforeEach, afterEach } from '@jest/globals';
 import { ModelManager } from './ModelManager';
-import type { ModelVariant } from './types';
+import type { AvailableModel, ModelSize } from './types';
 
 // Mock Tauri APIs
 jest.mock('@tauri-apps/api/core', () => ({
@@ -15,6 +15,31 @@ import { invoke } from '@tauri-apps/api/core';
 
 const mockInvoke = invoke as jest.MockedFunction<typeof invoke>;
 
+function catalogEntry(model_size: ModelSize, filename: string, sha256: string, memory_requirement: number): AvailableModel {
+  return {
+    variant: model_size,
+    filename,
+    mirrors: [`https://huggingface.co/ggerganov/whisper.cpp/resolve/main/${filename}`],
+    family: 'whisper',
+    model_size,
+    quantization: 'f16',
+    languages: 'multilingual',
+    size: 1024,
+    sha256,
+    memory_requirement,
+    downloaded: false,
+  };
+}
+
+// Backend catalog as returned by list_available_models
+const mockCatalog: AvailableModel[] = [
+  catalogEntry('tiny', 'ggml-tiny.bin', 'bd577a113a864445d4c299885e0cb97d4ba92b5f0a0f1e2d3b4c5d6e7f8a9b0c', 390 * 1024 * 1024),
+  catalogEntry('base', 'ggml-base.bin', 'cd577a113a864445d4c299885e0cb97d4ba92b5f0a0f1e2d3b4c5d6e7f8a9b0d', 500 * 1024 * 1024),
+  catalogEntry('small', 'ggml-small.bin', 'de577a113a864445d4c299885e0cb97d4ba92b5f0a0f1e2d3b4c5d6e7f8a9b0e', 1000 * 1024 * 1024),
+  catalogEntry('medium', 'ggml-medium.bin', 'ee577a113a864445d4c299885e0cb97d4ba92b5f0a0f1e2d3b4c5d6e7f8a9b0f', 2600 * 1024 * 1024),
+  catalogEntry('large', 'ggml-large-v3.bin', 'fe577a113a864445d4c299885e0cb97d4ba92b5f0a0f1e2d3b4c5d6e7f8a9b10', 4700 * 1024 * 1024),
+];
+
 describe('ModelManager - Startup Validation', () => {
   let modelManager: ModelManager;
   const mockModelsDirectory = '/mock/models/directory';
@@ -28,6 +53,9 @@ describe('ModelManager - Startup Validation', () => {
       if (cmd === 'get_models_directory') {
         return Promise.resolve(mockModelsDirectory as T);
       }
+      if (cmd === 'list_available_models') {
+        return Promise.resolve(mockCatalog as T);
+      }
       return Promise.reject(new Error(`Unmocked command: ${cmd}`));
     });
   });
@@ -36,6 +64,33 @@ describe('ModelManager - Startup Validation', () => {
     jest.restoreAllMocks();
   });
 
+  describe('initialize', () => {
+    it('should take model metadata from the backend catalog', async () => {
+      await modelManager.initialize();
+
+      const small = modelManager.getModelMetadata('small');
+      expect(small.filename).toBe('ggml-small.bin');
+      expect(small.checksum).toBe(mockCatalog[2].sha256);
+      expect(small.downloadUrl).toBe(mockCatalog[2].mirrors[0]);
+      expect(small.memoryRequirement).toBe(1000 * 1024 * 1024);
+      expect(small.accuracy).toBe('better');
+      expect(small.estimatedSpeed).toBe('medium');
+      expect(modelManager.getAllModelMetadata()).toHaveLength(mockCatalog.length);
+    });
+
+    it('should fail when the catalog cannot be loaded', async () => {
+      mockInvoke.mockImplementation(<T>(cmd: string): Promise<T> => {
+        if (cmd === 'get_models_directory') {
+          return Promise.resolve(mockModelsDirectory as T);
+        }
+        return Promise.reject(new Error(`Unmocked command: ${cmd}`));
+      });
+
+      await expect(modelManager.initialize()).rejects.toThrow('Unmocked command: list_available_models');
+      expect(() => modelManager.getModelMetadata('tiny')).toThrow('Unknown model variant: tiny');
+    });
+  });
+
   describe('validateAllModelsOnStartup', () => {
     it('should validate all model variants on startup', async () => {
       // Mock file_exists to return true for tiny and base models
@@ -44,6 +99,9 @@ describe('ModelManager - Startup Validation', () => {
         if (cmd === 'get_models_directory') {
           return Promise.resolve(mockModelsDirectory as T);
         }
+        if (cmd === 'list_available_models') {
+          return Promise.resolve(mockCatalog as T);
+        }
         if (cmd === 'file_exists') {
           const path = args?.path || '';
           return Promise.resolve(
@@ -102,6 +160,9 @@ describe('ModelManager - Startup Validation', () => {
         if (cmd === 'get_models_directory') {
           return Promise.resolve(mockModelsDirectory as T);
         }
+        if (cmd === 'list_available_models') {
+          return Promise.resolve(mockCatalog as T);
+        }
         if (cmd === 'file_exists') {
           const path = args?.path || '';
           // Tiny model exists but is corrupted
@@ -139,6 +200,9 @@ describe('ModelManager - Startup Validation', () => {
         if (cmd === 'get_models_directory') {
           return Promise.resolve(mockModelsDirectory as T);
         }
+        if (cmd === 'list_available_models') {
+          return Promise.resolve(mockCatalog as T);
+        }
         if (cmd === 'file_exists') {
           const path = args?.path || '';
           if (path.includes('tiny')) {
@@ -172,6 +236,9 @@ describe('ModelManager - Startup Validation', () => {
         if (cmd === 'get_models_directory') {
           return Promise.resolve(mockModelsDirectory as T);
         }
+        if (cmd === 'list_available_models') {
+          return Promise.resolve(mockCatalog as T);
+        }
         if (cmd === 'file_exists') {
           // All models exist
           return Promise.resolve(true as T);
@@ -217,6 +284,9 @@ describe('ModelManager - Startup Validation', () => {
         if (cmd === 'get_models_directory') {
           return Promise.resolve(mockModelsDirectory as T);
         }
+        if (cmd === 'list_available_models') {
+          return Promise.resolve(mockCatalog as T);
+        }
         if (cmd === 'file_exists') {
           return Promise.resolve(true as T);
         }
@@ -1,59 +1,88 @@
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import type { ModelVariant, ModelMetadata, DownloadProgress } from './types';
+import type {
+  AvailableModel,
+  CustomModel,
+  ModelFamily,
+  ModelSize,
+  ModelVariant,
+  ModelMetadata,
+  Quantization,
+  DownloadProgress,
+  DownloadProgressEvent,
+} from './types';
 
 export class ModelManager {
-  private readonly MODEL_METADATA: Record<ModelVariant, ModelMetadata> = {
-    tiny: {
-      variant: 'tiny',
-      size: 75 * 1024 * 1024, // 75 MB
-      checksum: 'bd577a113a864445d4c299885e0cb97d4ba92b5f0a0f1e2d3b4c5d6e7f8a9b0c',
-      downloadUrl: 'https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin',
-      accuracy: 'good',
-      estimatedSpeed: 'fast',
-    },
-    base: {
-      variant: 'base',
-      size: 142 * 1024 * 1024, // 142 MB
-      checksum: 'cd577a113a864445d4c299885e0cb97d4ba92b5f0a0f1e2d3b4c5d6e7f8a9b0d',
-      downloadUrl: 'https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin',
-      accuracy: 'better',
-      estimatedSpeed: 'fast',
-    },
-    small: {
-      variant: 'small',
-      size: 466 * 1024 * 1024, // 466 MB
-      checksum: 'de577a113a864445d4c299885e0cb97d4ba92b5f0a0f1e2d3b4c5d6e7f8a9b0e',
-      downloadUrl: 'https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin',
-      accuracy: 'better',
-      estimatedSpeed: 'medium',
-    },
-    medium: {
-      variant: 'medium',
-      size: 1.5 * 1024 * 1024 * 1024, // 1.5 GB
-      checksum: 'ee577a113a864445d4c299885e0cb97d4ba92b5f0a0f1e2d3b4c5d6e7f8a9b0f',
-      downloadUrl: 'https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin',
-      accuracy: 'best',
-      estimatedSpeed: 'slow',
-    },
-    large: {
-      variant: 'large',
-      size: 2.9 * 1024 * 1024 * 1024, // 2.9 GB
-      checksum: 'fe577a113a864445d4c299885e0cb97d4ba92b5f0a0f1e2d3b4c5d6e7f8a9b10',
-      downloadUrl: 'https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3.bin',
-      accuracy: 'best',
-      estimatedSpeed: 'slow',
-    },
-  };
+  // Filled from the backend catalog and the custom model registry by initialize()
+  private readonly MODEL_METADATA: Partial<Record<ModelVariant, ModelMetadata>> = {};
 
   private modelsDirectory: string | null = null;
   private downloadProgressCallbacks: Map<
     ModelVariant,
     (progress: DownloadProgress) => void
   > = new Map();
+  private activeDownloads: Map<ModelVariant, string> = new Map();
 
   async initialize(): Promise<void> {
     this.modelsDirectory = await invoke<string>('get_models_directory');
+    await this.loadCatalog();
+  }
+
+  /**
+   * Take model metadata from the backend catalog, which is what downloads
+   * are verified against
+   */
+  private async loadCatalog(): Promise<void> {
+    const models = await invoke<AvailableModel[]>('list_available_models');
+    for (const model of models) {
+      this.MODEL_METADATA[model.variant] = {
+        variant: model.variant,
+        filename: model.filename,
+        size: model.size,
+        checksum: model.sha256,
+        downloadUrl: model.mirrors[0] ?? '',
+        memoryRequirement: model.memory_requirement,
+        accuracy: estimateAccuracy(model.model_size),
+        estimatedSpeed: estimateSpeed(model.model_size, model.family, model.quantization),
+      };
+    }
+
+    try {
+      const customModels = await invoke<CustomModel[]>('list_custom_models');
+      customModels.forEach((model) => this.addCustomModel(model));
+    } catch (error) {
+      console.warn('Failed to load custom models:', error);
+    }
+  }
+
+  private addCustomModel(model: CustomModel): void {
+    // Non-standard architectures are at least as large as the medium models
+    const modelSize = model.model_size ?? 'medium';
+    this.MODEL_METADATA[model.variant] = {
+      variant: model.variant,
+      filename: model.filename,
+      size: model.size,
+      checksum: model.sha256,
+      downloadUrl: '', // Imported, never downloaded
+      memoryRequirement: model.memory_requirement,
+      accuracy: estimateAccuracy(modelSize),
+      estimatedSpeed: estimateSpeed(modelSize, model.family, model.quantization),
+    };
+  }
+
+  /**
+   * Import a ggml model file as a custom variant
+   * @param link - Link to the file instead of copying it into the models directory
+   */
+  async importModel(path: string, name?: string, link = false): Promise<CustomModel> {
+    const model = await invoke<CustomModel>('import_model', { path, name: name ?? null, link });
+    this.addCustomModel(model);
+    return model;
+  }
+
+  async removeCustomModel(variant: ModelVariant): Promise<void> {
+    await invoke('remove_custom_model', { variant });
+    delete this.MODEL_METADATA[variant];
   }
 
   async downloadModel(
@@ -64,32 +93,67 @@ export class ModelManager {
       await this.initialize();
     }
 
-    const metadata = this.MODEL_METADATA[variant];
-    const targetPath = this.getModelPath(variant);
+    const metadata = this.getModelMetadata(variant);
 
     if (onProgress) {
       this.downloadProgressCallbacks.set(variant, onProgress);
     }
 
-    // Listen for download progress events
-    const unlisten = await listen<any>('download-progress', (event) => {
-      const progress: DownloadProgress = {
+    // Events can arrive before start_download returns, so buffer them until
+    // the download ID is known
+    let downloadId: string | null = null;
+    const pending: DownloadProgressEvent[] = [];
+    let settle: ((event: DownloadProgressEvent) => void) | null = null;
+    const finished = new Promise<DownloadProgressEvent>((resolve) => {
+      settle = resolve;
+    });
+
+    const handle = (payload: DownloadProgressEvent) => {
+      // Completion, cancellation and failure are reported below
+      if (payload.status === 'completed' || payload.status === 'cancelled' || payload.status === 'failed') {
+        settle?.(payload);
+        return;
+      }
+      onProgress?.({
         variant,
-        bytesDownloaded: event.payload.bytes_downloaded,
-        totalBytes: event.payload.total_bytes,
-        percentage: event.payload.percentage,
-        status: event.payload.status,
-      };
-      onProgress?.(progress);
+        downloadId: payload.download_id,
+        bytesDownloaded: payload.bytes_downloaded,
+        totalBytes: payload.total_bytes,
+        percentage: payload.percentage,
+        bytesPerSecond: payload.bytes_per_second,
+        status: payload.status,
+      });
+    };
+
+    const unlisten = await listen<DownloadProgressEvent>('download-progress', (event) => {
+      if (downloadId === null) {
+        pending.push(event.payload);
+      } else if (event.payload.download_id === downloadId) {
+        handle(event.payload);
+      }
     });
 
     try {
-      await invoke('download_model', {
-        url: metadata.downloadUrl,
-        targetPath,
-        expectedSize: metadata.size,
-        checksum: metadata.checksum,
-      });
+      downloadId = await invoke<string>('download_model_variant', { variant });
+      this.activeDownloads.set(variant, downloadId);
+      pending.filter((payload) => payload.download_id === downloadId).forEach(handle);
+
+      const outcome = await finished;
+      if (outcome.status === 'cancelled') {
+        onProgress?.({
+          variant,
+          downloadId,
+          bytesDownloaded: 0,
+          totalBytes: metadata.size,
+          percentage: 0,
+          bytesPerSecond: 0,
+          status: 'cancelled',
+        });
+        return;
+      }
+      if (outcome.status === 'failed') {
+        throw new Error(`Download failed for ${variant}`);
+      }
 
       // Validate after download
       const isValid = await this.validateModel(variant);
@@ -100,28 +164,59 @@ export class ModelManager {
 
       onProgress?.({
         variant,
+        downloadId,
         bytesDownloaded: metadata.size,
         totalBytes: metadata.size,
         percentage: 100,
+        bytesPerSecond: 0,
         status: 'complete',
       });
     } catch (error) {
       onProgress?.({
         variant,
+        downloadId: downloadId ?? undefined,
         bytesDownloaded: 0,
         totalBytes: metadata.size,
         percentage: 0,
+        bytesPerSecond: 0,
         status: 'error',
       });
       throw error;
     } finally {
       unlisten();
+      this.activeDownloads.delete(variant);
       this.downloadProgressCallbacks.delete(variant);
     }
   }
 
+  /**
+   * Pause a running download; the partial file is kept for resumeDownload
+   */
+  async pauseDownload(variant: ModelVariant): Promise<void> {
+    await invoke('pause_download', { downloadId: this.requireDownload(variant) });
+  }
+
+  async resumeDownload(variant: ModelVariant): Promise<void> {
+    await invoke('resume_download', { downloadId: this.requireDownload(variant) });
+  }
+
+  /**
+   * Cancel a download and delete its partial file
+   */
+  async cancelDownload(variant: ModelVariant): Promise<void> {
+    await invoke('cancel_download', { downloadId: this.requireDownload(variant) });
+  }
+
+  private requireDownload(variant: ModelVariant): string {
+    const downloadId = this.activeDownloads.get(variant);
+    if (!downloadId) {
+      throw new Error(`No download in progress for ${variant}`);
+    }
+    return downloadId;
+  }
+
   async validateModel(variant: ModelVariant): Promise<boolean> {
-    const metadata = this.MODEL_METADATA[variant];
+    const metadata = this.getModelMetadata(variant);
     const modelPath = this.getModelPath(variant);
 
     try {
@@ -164,18 +259,24 @@ export class ModelManager {
   }
 
   getModelMetadata(variant: ModelVariant): ModelMetadata {
-    return this.MODEL_METADATA[variant];
+    const metadata = this.MODEL_METADATA[variant];
+    if (!metadata) {
+      throw new Error(`Unknown model variant: ${variant}`);
+    }
+    return metadata;
   }
 
   getAllModelMetadata(): ModelMetadata[] {
-    return Object.values(this.MODEL_METADATA);
+    return Object.values(this.MODEL_METADATA).filter(
+      (metadata): metadata is ModelMetadata => metadata !== undefined
+    );
   }
 
   getModelPath(variant: ModelVariant): string {
     if (!this.modelsDirectory) {
       throw new Error('ModelManager not initialized');
     }
-    return `${this.modelsDirectory}/ggml-${variant}.bin`;
+    return `${this.modelsDirectory}/${this.getModelMetadata(variant).filename}`;
   }
 
   async getAvailableDiskSpace(): Promise<number> {
@@ -193,7 +294,7 @@ export class ModelManager {
    * @returns true if the model fits, false otherwise
    */
   async canModelFit(variant: ModelVariant): Promise<boolean> {
-    const metadata = this.MODEL_METADATA[variant];
+    const metadata = this.getModelMetadata(variant);
     const availableSpace = await this.getAvailableDiskSpace();
     return availableSpace >= metadata.size;
   }
@@ -277,6 +378,40 @@ export class ModelManager {
   }
 }
 
+function estimateAccuracy(modelSize: ModelSize): ModelMetadata['accuracy'] {
+  switch (modelSize) {
+    case 'tiny':
+      return 'good';
+    case 'base':
+    case 'small':
+      return 'better';
+    default:
+      return 'best';
+  }
+}
+
+/**
+ * Quantized medium models and the turbo and distilled large models run a
+ * size class faster than full-precision Whisper
+ */
+function estimateSpeed(
+  modelSize: ModelSize,
+  family: ModelFamily,
+  quantization: Quantization
+): ModelMetadata['estimatedSpeed'] {
+  switch (modelSize) {
+    case 'tiny':
+    case 'base':
+      return 'fast';
+    case 'small':
+      return 'medium';
+    case 'medium':
+      return quantization === 'f16' || quantization === 'f32' ? 'slow' : 'medium';
+    case 'large':
+      return family === 'whisper' ? 'slow' : 'medium';
+  }
+}
+
 // Singleton instance
 let modelManagerInstance: ModelManager | null = null;
 
@@ -4,7 +4,7 @@
 
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import type { ModelVariant, TranscriptionProgress } from './types';
+import type { ModelVariant, TranscriptionProgress, WhisperModelStatus } from './types';
 
 /**
  * Example: Transcribe audio with progress tracking
@@ -90,13 +90,17 @@ export async function completeTranscriptionWorkflow(
  * Example: Check if a model is currently loaded
  */
 export async function checkModelStatus(): Promise<ModelVariant | null> {
-  const status = await invoke<ModelVariant | null>('get_whisper_model_status');
+  const status = await invoke<WhisperModelStatus>('get_whisper_model_status');
   
-  if (status) {
-    console.log(`Model ${status} is currently loaded`);
+  if (status.state === 'ready') {
+    console.log(`Model ${status.variant} is currently loaded`);
+  } else if (status.state === 'loading') {
+    console.log(`Model ${status.variant} is loading`);
+  } else if (status.state === 'failed') {
+    console.log(`Loading model ${status.variant} failed: ${status.error}`);
   } else {
     console.log('No model is currently loaded');
   }
   
-  return status;
+  return status.state === 'ready' ? status.variant : null;
 }
@@ -2,28 +2,157 @@
  * Types for Whisper transcription service
  */
 
-export type ModelVariant = 'tiny' | 'base' | 'small' | 'medium' | 'large';
+export type ModelVariant =
+  | 'tiny'
+  | 'tiny.en'
+  | 'tiny-q5_1'
+  | 'base'
+  | 'base.en'
+  | 'base-q5_1'
+  | 'base.en-q5_1'
+  | 'small'
+  | 'small.en'
+  | 'small-q5_1'
+  | 'medium'
+  | 'medium.en'
+  | 'medium-q5_0'
+  | 'large' // large-v3
+  | 'large-v3-q5_0'
+  | 'large-v3-turbo'
+  | 'large-v3-turbo-q5_0'
+  | 'large-v3-turbo-q8_0'
+  | 'distil-large-v3'
+  | `custom-${string}`; // Imported with `import_model`
 
 export interface ModelMetadata {
   variant: ModelVariant;
+  filename: string;
   size: number; // bytes
   checksum: string;
   downloadUrl: string;
+  memoryRequirement: number; // bytes, model plus one inference state
   accuracy: 'good' | 'better' | 'best';
   estimatedSpeed: 'fast' | 'medium' | 'slow';
 }
 
 export interface DownloadProgress {
   variant: ModelVariant;
+  downloadId?: string;
   bytesDownloaded: number;
   totalBytes: number;
   percentage: number;
-  status: 'downloading' | 'validating' | 'complete' | 'error';
+  bytesPerSecond: number; // Smoothed throughput, 0 while not transferring
+  status:
+    | 'starting'
+    | 'downloading'
+    | 'resumed' // Continuing a partial download
+    | 'retrying'
+    | 'paused'
+    | 'validating'
+    | 'cancelled'
+    | 'complete'
+    | 'error';
+}
+
+export type LanguageSupport = 'multilingual' | 'english_only';
+export type ModelFamily = 'whisper' | 'turbo' | 'distil';
+export type ModelSize = 'tiny' | 'base' | 'small' | 'medium' | 'large';
+export type Quantization = 'f32' | 'f16' | 'q4_0' | 'q4_1' | 'q5_0' | 'q5_1' | 'q8_0';
+
+/**
+ * A model from the backend catalog returned by `list_available_models`
+ */
+export interface AvailableModel {
+  variant: ModelVariant;
+  filename: string;
+  mirrors: string[]; // Preferred mirror first
+  family: ModelFamily;
+  model_size: ModelSize;
+  quantization: Quantization;
+  languages: LanguageSupport;
+  size: number; // bytes
+  sha256: string;
+  memory_requirement: number; // bytes
+  downloaded: boolean;
+}
+
+/**
+ * Hyperparameters read from a ggml model header
+ */
+export interface ModelHeader {
+  n_vocab: number;
+  n_audio_ctx: number;
+  n_audio_state: number;
+  n_audio_head: number;
+  n_audio_layer: number;
+  n_text_ctx: number;
+  n_text_state: number;
+  n_text_head: number;
+  n_text_layer: number;
+  n_mels: number;
+  ftype: number;
+}
+
+/**
+ * A user-supplied model returned by `import_model` and `list_custom_models`
+ */
+export interface CustomModel {
+  variant: ModelVariant;
+  name: string;
+  filename: string;
+  source_path: string;
+  linked: boolean; // The models directory links to source_path instead of holding a copy
+  imported_at: number; // Unix time in milliseconds
+  size: number; // bytes
+  sha256: string;
+  header: ModelHeader;
+  family: ModelFamily;
+  model_size: ModelSize | null; // null for non-standard architectures
+  quantization: Quantization;
+  languages: LanguageSupport;
+  memory_requirement: number; // bytes
+}
+
+export type DownloadStatus =
+  | 'starting'
+  | 'downloading'
+  | 'resumed'
+  | 'retrying'
+  | 'paused'
+  | 'validating'
+  | 'completed'
+  | 'cancelled'
+  | 'failed';
+
+/**
+ * Payload of the 'download-progress' event
+ */
+export interface DownloadProgressEvent {
+  download_id: string;
+  bytes_downloaded: number;
+  total_bytes: number;
+  percentage: number;
+  bytes_per_second: number;
+  status: DownloadStatus;
+}
+
+/**
+ * A running or paused download returned by `list_downloads`
+ */
+export interface DownloadInfo {
+  download_id: string;
+  url: string;
+  target_path: string;
+  progress: DownloadProgressEvent;
 }
 
 export interface TranscriptionProgress {
+  job_id?: string | null; // Set when the transcription runs as a job
   stage: 'loading_model' | 'processing_audio' | 'finalizing' | 'complete';
   progress: number; // 0.0 to 1.0
+  elapsed_ms?: number; // Time since the transcription started
+  eta_ms?: number | null; // Estimated time remaining, once whisper reported progress
+  latest_segment?: string | null; // Most recently decoded segment text
 }
 
 export interface TranscriptionResult {
@@ -33,6 +162,179 @@ export interface TranscriptionResult {
   confidence?: number;
 }
 
+/**
+ * A transcribed segment as returned by the Rust backend (times in milliseconds)
+ */
+export interface TranscriptionSegment {
+  t0: number;
+  t1: number;
+  text: string;
+  no_speech_probability: number;
+  words: TranscriptionWord[]; // Empty unless word_timestamps was requested
+}
+
+/**
+ * Word timing and confidence, filled in when `word_timestamps` is enabled
+ */
+export interface TranscriptionWord {
+  text: string;
+  t0: number; // Milliseconds
+  t1: number;
+  probability: number; // Mean token probability, 0.0 to 1.0
+}
+
+/**
+ * Segment-level transcription result returned by `transcribe_audio_segments`
+ */
+export interface SegmentedTranscription {
+  text: string;
+  segments: TranscriptionSegment[];
+  language: string;
+  language_probability: number | null; // Only set when the language was auto-detected
+}
+
+/**
+ * Language and task options accepted by the local transcription commands
+ */
+export interface LocalTranscriptionOptions {
+  language?: string; // ISO 639-1 code (e.g. 'de') or 'auto'; defaults to 'en'
+  translate?: boolean; // Translate the output to English
+  vad?: VadOptions; // Silence trimming and chunking before decoding
+  word_timestamps?: boolean; // Per-word timing and confidence on each segment
+  preset?: DecodingPreset; // Starting point for `decoding` (default: 'balanced')
+  decoding?: DecodingConfig; // Search strategy, thresholds and threads; set fields override the preset
+}
+
+/**
+ * How whisper picks tokens
+ */
+export type SearchStrategy =
+  | { type: 'greedy'; best_of: number } // 1-16
+  | { type: 'beam_search'; beam_size: number; patience: number }; // beam_size 1-16, patience -1 for default
+
+export type DecodingPreset = 'fast' | 'balanced' | 'accurate';
+
+/**
+ * Decoder settings, validated by the backend. Start from `list_decoding_presets`.
+ */
+export interface DecodingConfig {
+  strategy?: SearchStrategy;
+  temperature?: number; // 0.0-1.0
+  temperature_increment?: number; // Fallback step, 0.0 disables fallback
+  entropy_threshold?: number; // Default 2.4
+  logprob_threshold?: number; // Default -1.0
+  no_speech_threshold?: number; // Default 0.6
+  max_segment_length?: number; // Characters, 0 for no limit
+  suppress_blank?: boolean;
+  suppress_non_speech_tokens?: boolean;
+  n_threads?: number | null; // null uses the threads assigned to the job
+}
+
+export interface NamedDecodingConfig {
+  preset: DecodingPreset;
+  config: Required<DecodingConfig>;
+}
+
+/**
+ * Layout of raw PCM passed to the transcription commands. The backend
+ * downmixes, removes DC offset, resamples to 16kHz and peak normalizes.
+ */
+export interface AudioFormat {
+  sample_rate?: number; // Hz, 8000-384000 (default 16000)
+  channels?: number; // Interleaved channel count (default 1)
+}
+
+/**
+ * Energy based voice activity detection run before whisper. Leading and
+ * trailing silence is trimmed and long recordings are split at pauses;
+ * segment timestamps still refer to the original recording.
+ */
+export interface VadOptions {
+  enabled?: boolean; // Defaults to false
+  threshold_ratio?: number; // Speech threshold as a multiple of the noise floor (default 3.0)
+  min_threshold?: number; // Absolute RMS floor (default 0.002)
+  min_silence_ms?: number; // Shortest pause that may end a chunk (default 500)
+  max_chunk_ms?: number; // Longer chunks are split at the quietest point (default 30000)
+  padding_ms?: number; // Audio kept around detected speech (default 200)
+}
+
+/**
+ * Payload of the 'transcription-partial' event emitted by streaming sessions
+ */
+export interface TranscriptionPartial {
+  session_id: string;
+  committed_text: string; // Text that will no longer change
+  tentative_text: string; // Text of the current window, may be revised
+  is_final: boolean;
+}
+
+export type TranscriptionJobStatus = 'queued' | 'running' | 'completed' | 'failed' | 'cancelled';
+
+/**
+ * Queue priority: hotkey dictation runs before normal jobs, batch re-transcription last
+ */
+export type TranscriptionPriority = 'dictation' | 'normal' | 'batch';
+
+/**
+ * Payload of the 'transcription-queue' event
+ */
+export interface TranscriptionQueueStatus {
+  queued: number;
+  running: number; // Across all models
+  max_concurrent: number; // Per loaded model
+}
+
+/**
+ * Snapshot of a transcription job, also the payload of the 'transcription-job' event
+ */
+export interface TranscriptionJobInfo {
+  job_id: string;
+  status: TranscriptionJobStatus;
+  priority: TranscriptionPriority;
+  variant: ModelVariant;
+  result: SegmentedTranscription | null;
+  error: string | null;
+}
+
+export type ModelLoadState = 'unloaded' | 'loading' | 'ready' | 'failed';
+
+/**
+ * Model lifecycle state returned by `get_whisper_model_status`
+ */
+export interface WhisperModelStatus {
+  state: ModelLoadState;
+  variant: ModelVariant | null;
+  error: string | null;
+}
+
+/**
+ * Payload of the 'model-load-progress' event
+ */
+export interface ModelLoadProgress {
+  variant: ModelVariant;
+  stage: 'loading' | 'warming_up' | 'ready' | 'failed' | 'evicted';
+  progress: number; // 0.0 to 1.0
+  error: string | null;
+}
+
+/**
+ * A model held in the backend model pool
+ */
+export interface PooledModelInfo {
+  variant: ModelVariant;
+  estimated_memory: number; // bytes
+  idle_ms: number; // Time since the model was last used
+}
+
+/**
+ * Model pool state returned by `get_model_pool_status` (most recently used first)
+ */
+export interface ModelPoolStatus {
+  models: PooledModelInfo[];
+  memory_used: number;
+  memory_budget: number;
+}
+
 export interface WhisperModel {
   variant: ModelVariant;
   path: string;
@@ -62,37 +364,209 @@ export interface TranscriptionSettings {
  */
 export interface WhisperCommands {
   /**
-   * Load a Whisper model into memory
+   * Load a Whisper model into memory on a background worker, then warm it up.
+   * Resolves once the model is ready; progress is emitted via 'model-load-progress'.
    * @param path - Full path to the model file
    * @param variant - Model variant (tiny, base, small, medium, large)
    */
   load_whisper_model(path: string, variant: ModelVariant): Promise<void>;
 
   /**
-   * Unload the currently loaded Whisper model from memory
+   * Unload a Whisper model from memory, or all loaded models when no variant is given
    */
-  unload_whisper_model(): Promise<void>;
+  unload_whisper_model(variant?: ModelVariant): Promise<void>;
 
   /**
-   * Transcribe audio data using the loaded Whisper model
-   * @param audioData - Float32Array of PCM audio samples (16kHz, mono)
-   * @param variant - Model variant (for reference, not used if model already loaded)
+   * Transcribe audio data using the requested Whisper model
+   * @param audioData - PCM samples, interleaved when multi-channel
+   * @param variant - Model variant; loaded from the models directory if not in the pool
+   * @param options - Language / translation options (defaults to English transcription)
+   * @param format - Layout of audioData (defaults to 16kHz mono); converted in the backend
    * @returns Transcribed text
    * 
    * Progress events are emitted via 'transcription-progress' event:
    * - loading_model (0.0)
-   * - processing_audio (0.33)
-   * - finalizing (0.66)
+   * - processing_audio (whisper's real progress, plus each newly decoded segment)
+   * - finalizing (1.0)
    * - complete (1.0)
    */
   transcribe_audio(
     audioData: number[],
-    variant: ModelVariant
+    variant: ModelVariant,
+    options?: LocalTranscriptionOptions,
+    format?: AudioFormat
+  ): Promise<string>;
+
+  /**
+   * Transcribe audio data and keep whisper's segment boundaries
+   * @param audioData - PCM samples, interleaved when multi-channel
+   * @param variant - Model variant; loaded from the models directory if not in the pool
+   * @param options - Language / translation options (defaults to English transcription)
+   * @param format - Layout of audioData (defaults to 16kHz mono); converted in the backend
+   * @returns Full text plus per-segment timestamps, no-speech probabilities and the language used
+   */
+  transcribe_audio_segments(
+    audioData: number[],
+    variant: ModelVariant,
+    options?: LocalTranscriptionOptions,
+    format?: AudioFormat
+  ): Promise<SegmentedTranscription>;
+
+  /**
+   * Transcribe an audio file from disk; decoding, downmixing and resampling
+   * to 16kHz mono happen in the backend
+   * @param path - WAV, FLAC, MP3, Ogg/Vorbis or Ogg/Opus file
+   * @param variant - Model variant; loaded from the models directory if not in the pool
+   * @param options - Language / translation options (defaults to English transcription)
+   * @returns Full text plus per-segment timestamps, no-speech probabilities and the language used
+   */
+  transcribe_file(
+    path: string,
+    variant: ModelVariant,
+    options?: LocalTranscriptionOptions
+  ): Promise<SegmentedTranscription>;
+
+  /**
+   * List the named decoding presets ('fast', 'balanced', 'accurate') with
+   * their settings. Select one with `options.preset`; fields set in
+   * `options.decoding` override it
+   */
+  list_decoding_presets(): Promise<NamedDecodingConfig[]>;
+
+  /**
+   * Start a streaming transcription session
+   * @param variant - Model to use; defaults to the most recently used loaded model
+   * @param options - Language / translation options for the whole session
+   * @returns Session ID to pass to push/finish
+   */
+  start_transcription_session(
+    variant?: ModelVariant,
+    options?: LocalTranscriptionOptions
+  ): Promise<string>;
+
+  /**
+   * Push a chunk of 16kHz mono audio into a streaming session.
+   * Emits 'transcription-partial' events whenever the window is re-transcribed.
+   */
+  push_transcription_audio(sessionId: string, audioChunk: number[]): Promise<void>;
+
+  /**
+   * Transcribe the remaining audio, close the session and return the full result
+   */
+  finish_transcription_session(sessionId: string): Promise<SegmentedTranscription>;
+
+  /**
+   * Close a streaming session and discard its audio. Sessions idle for
+   * five minutes are closed automatically.
+   */
+  cancel_transcription_session(sessionId: string): Promise<void>;
+
+  /**
+   * Queue a cancellable transcription job
+   * @param priority - Scheduling priority (defaults to 'normal')
+   * @param format - Layout of audioData (defaults to 16kHz mono); converted in the backend
+   * @returns Job ID; the outcome is reported via 'transcription-job' events
+   */
+  start_transcription(
+    audioData: number[],
+    variant: ModelVariant,
+    options?: LocalTranscriptionOptions,
+    priority?: TranscriptionPriority,
+    format?: AudioFormat
   ): Promise<string>;
 
   /**
-   * Get the status of the currently loaded model
-   * @returns The variant of the loaded model, or null if no model is loaded
+   * Abort a running transcription job; it then reports the 'cancelled' status
+   */
+  cancel_transcription(jobId: string): Promise<void>;
+
+  /**
+   * Get the current state (and result, once finished) of a transcription job
+   */
+  get_transcription_job(jobId: string): Promise<TranscriptionJobInfo>;
+
+  /**
+   * List queued, running and recently finished jobs in enqueue order
+   */
+  list_transcription_jobs(): Promise<TranscriptionJobInfo[]>;
+
+  /**
+   * Get queue counters; changes are also emitted via 'transcription-queue' events
+   */
+  get_transcription_queue_status(): Promise<TranscriptionQueueStatus>;
+
+  /**
+   * Set how many transcriptions may run at once against the loaded model
+   */
+  set_transcription_concurrency(maxConcurrent: number): Promise<TranscriptionQueueStatus>;
+
+  /**
+   * Get the model lifecycle state
+   * @param variant - Variant to query; defaults to the most recently requested one
+   * @returns unloaded / loading / ready / failed, with the variant and any load error
+   */
+  get_whisper_model_status(variant?: ModelVariant): Promise<WhisperModelStatus>;
+
+  /**
+   * List the models held in the pool with their memory estimates
+   */
+  get_model_pool_status(): Promise<ModelPoolStatus>;
+
+  /**
+   * Set the pool memory budget in bytes (null restores the default of half the
+   * system memory); least recently used models are evicted to fit
+   */
+  set_model_memory_budget(budget: number | null): Promise<ModelPoolStatus>;
+
+  /**
+   * List the models in the backend catalog and whether each is downloaded
+   */
+  list_available_models(): Promise<AvailableModel[]>;
+
+  /**
+   * Start downloading a catalog model into the models directory. URL, size and
+   * checksum come from the catalog.
+   * @returns Download ID; progress is emitted via 'download-progress'
+   */
+  download_model_variant(variant: ModelVariant): Promise<string>;
+
+  /**
+   * Copy or link a ggml model into the models directory after validating its
+   * header, and register it as a custom variant. GGUF files are rejected;
+   * whisper.cpp only loads its own ggml format.
+   * @param path - Model file to import
+   * @param name - Display name; defaults to the file name
+   * @param link - Link to the file instead of copying it
+   */
+  import_model(path: string, name: string | null, link: boolean): Promise<CustomModel>;
+
+  /**
+   * List imported models
+   */
+  list_custom_models(): Promise<CustomModel[]>;
+
+  /**
+   * Unload and forget an imported model; linked source files are kept
+   */
+  remove_custom_model(variant: ModelVariant): Promise<void>;
+
+  /**
+   * Pause a download, keeping the partial file so it can be resumed
+   */
+  pause_download(downloadId: string): Promise<void>;
+
+  /**
+   * Continue a paused download from where it stopped
+   */
+  resume_download(downloadId: string): Promise<void>;
+
+  /**
+   * Stop a download and delete its partial file
+   */
+  cancel_download(downloadId: string): Promise<void>;
+
+  /**
+   * List running and paused downloads
    */
-  get_whisper_model_status(): Promise<ModelVariant | null>;
+  list_downloads(): Promise<DownloadInfo[]>;
 }